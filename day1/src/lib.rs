@@ -1,40 +1,448 @@
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
 
-use utils;
+use utils::parse::{self, ParseError};
+use utils::Context;
 
-fn get_content() -> (Vec<i64>, Vec<i64>) {
-    let lines = utils::file_to_string_vector("day1\\data.txt");
-    let mut col1 = vec![];
-    let mut col2 = vec![];
+/// The n-th smallest IDs of both lists
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Pair {
+    pub left: i64,
+    pub right: i64,
+    /// Wider than the IDs, the distance of two i64 IDs does not fit into an i64
+    pub distance: i128,
+}
 
-    for line in lines {
-        let parts: Vec<i64> = line
-            .split(" ")
-            .filter(|&x| !x.is_empty())
-            .map(|x| x.parse::<i64>().unwrap())
-            .collect();
-        col1.push(parts[0]);
-        col2.push(parts[1]);
+/// What one ID of the left list adds to the similarity score
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Contribution {
+    pub id: i64,
+    /// How often the ID is in the left and in the right list
+    pub left_count: i64,
+    pub right_count: i64,
+    /// Wider than the IDs, like the distance
+    pub score: i128,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Reconciliation {
+    /// The n-th pair holds the n-th smallest ID of each list
+    pub pairs: Vec<Pair>,
+    /// Sorted by ID, only IDs of the left list
+    pub contributions: Vec<Contribution>,
+}
+
+/// Reads the two lists row by row, `delimiter` separates the columns and a whitespace delimiter means any run of whitespace
+/// Every row with a wrong column count or an invalid ID is reported, as are lists of unequal length,
+/// which happens when a row has an empty cell
+pub fn reconcile<I, S>(lines: I, delimiter: &str) -> Result<Reconciliation, Vec<ParseError>>
+where
+    I: IntoIterator<Item = S>,
+    S: AsRef<str>,
+{
+    let mut left = vec![];
+    let mut right = vec![];
+    let mut errors = vec![];
+    // Row of the first empty cell, where the lists started to differ
+    let mut first_gap = None;
+
+    for (i, line) in lines.into_iter().enumerate() {
+        let line = parse::Field::at(line.as_ref(), i + 1, 1);
+        if line.trim().is_empty() { continue }
+        let row = parse_row(line, delimiter, &mut left, &mut right);
+        match row {
+            Ok(true) => (),
+            Ok(false) => { first_gap.get_or_insert(line.line()); },
+            Err(e) => errors.push(e),
+        }
+    }
+
+    if left.len() != right.len() {
+        errors.push(ParseError {
+            line: first_gap.unwrap_or(1),
+            column: 1,
+            message: format!("the left list has {} IDs, the right list has {}", left.len(), right.len()),
+        });
+    }
+    if !errors.is_empty() { return Err(errors) }
+
+    left.sort();
+    right.sort();
+    let pairs = left
+        .iter()
+        .zip(right.iter())
+        .map(|(&left, &right)| Pair { left, right, distance: (left as i128 - right as i128).abs() })
+        .collect();
+
+    let mut counts: BTreeMap<i64, (i64, i64)> = BTreeMap::new();
+    for id in left.iter() { counts.entry(*id).or_default().0 += 1; }
+    for id in right.iter() {
+        if let Some(count) = counts.get_mut(id) { count.1 += 1; }
     }
-    col1.sort();
-    col2.sort();
-    (col1, col2)
+    let contributions = counts
+        .into_iter()
+        .map(|(id, (left_count, right_count))| Contribution { id, left_count, right_count, score: id as i128 * left_count as i128 * right_count as i128 })
+        .collect();
+
+    Ok(Reconciliation { pairs, contributions })
 }
 
-pub fn main_day1_task1() {
-    let (col1, col2) = get_content();
-    let result: i64 = col1.iter().zip(col2.iter()).map(|(value1, value2)| (value1 - value2).abs()).sum();
-    println!("Day 1 task 1 result is {}", result);
+// Adds the IDs of a row to the lists, false if one of the cells was empty
+// Nothing is added for an invalid row
+fn parse_row(line: parse::Field, delimiter: &str, left: &mut Vec<i64>, right: &mut Vec<i64>) -> parse::Result<bool> {
+    let cells = line.split(delimiter);
+    if cells.len() != 2 { return Err(line.error(format!("expected 2 columns, found {}", cells.len()))) }
+
+    let ids = cells
+        .iter()
+        .map(|cell| if cell.trim().is_empty() { Ok(None) } else { cell.parse().map(Some) })
+        .collect::<parse::Result<Vec<Option<i64>>>>()?;
+    left.extend(ids[0]);
+    right.extend(ids[1]);
+    Ok(ids.iter().all(|id| id.is_some()))
+}
+
+impl Reconciliation {
+    pub fn total_distance(&self) -> i128 {
+        self.pairs.iter().map(|pair| pair.distance).sum()
+    }
+
+    pub fn similarity(&self) -> i128 {
+        self.contributions.iter().map(|c| c.score).sum()
+    }
+
+    /// The `n` pairs furthest apart, largest first
+    pub fn largest_discrepancies(&self, n: usize) -> Vec<Pair> {
+        let mut pairs = self.pairs.clone();
+        pairs.sort_by_key(|pair| std::cmp::Reverse(pair.distance));
+        pairs.truncate(n);
+        pairs
+    }
+
+    pub fn write_pairs_csv(&self, mut writer: impl Write) -> io::Result<()> {
+        writeln!(writer, "left,right,distance")?;
+        for pair in self.pairs.iter() {
+            writeln!(writer, "{},{},{}", pair.left, pair.right, pair.distance)?;
+        }
+        Ok(())
+    }
+
+    pub fn write_contributions_csv(&self, mut writer: impl Write) -> io::Result<()> {
+        writeln!(writer, "id,left_count,right_count,score")?;
+        for c in self.contributions.iter() {
+            writeln!(writer, "{},{},{},{}", c.id, c.left_count, c.right_count, c.score)?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Side {
+    Left,
+    Right,
+}
+
+/// Both lists under insertions and removals of IDs, with the answers of both tasks kept up to date
+/// Any i64 is a valid ID, the answers are i128 as they can go beyond i64 then
+///
+/// The total distance is the sum over all integers x of |#{left IDs <= x} - #{right IDs <= x}|,
+/// an update adds +-1 to that difference for every x from the ID on, see `Difference`.
+/// An update of the similarity takes O(1), an update of the distance O(log(range) + k) where range is 2^64
+/// and k is the number of places from the ID on where the difference changes its sign. That is not
+/// logarithmic in the worst case: with closely interleaved lists, e.g. left 1, 3, 5, ... and right 2, 4, 6, ...,
+/// k grows with the number of IDs and an update takes O(n * log(range)).
+#[derive(Debug, Default)]
+pub struct LocationLists {
+    // How often an ID is in the left and in the right list
+    counts: HashMap<i64, [i64; 2]>,
+    lens: [usize; 2],
+    similarity: i128,
+    difference: Difference,
+}
+
+impl LocationLists {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn len(&self, side: Side) -> usize {
+        self.lens[side as usize]
+    }
+
+    pub fn insert(&mut self, side: Side, id: i64) {
+        self.update(side, id, 1);
+    }
+
+    /// False if the ID is not in the list
+    pub fn remove(&mut self, side: Side, id: i64) -> bool {
+        if self.counts.get(&id).is_none_or(|counts| counts[side as usize] == 0) { return false }
+        self.update(side, id, -1);
+        true
+    }
+
+    fn update(&mut self, side: Side, id: i64, delta: i64) {
+        let counts = self.counts.entry(id).or_default();
+        counts[side as usize] += delta;
+        self.similarity += (delta * counts[1 - side as usize]) as i128 * id as i128;
+        if counts == &[0, 0] { self.counts.remove(&id); }
+
+        let i = side as usize;
+        self.lens[i] = self.lens[i].checked_add_signed(delta as isize).unwrap();
+        let delta = if side == Side::Left { delta } else { -delta };
+        self.difference.add(id, delta);
+    }
+
+    /// Sum of the distances of the sorted pairs, None while the lists are not equally long
+    pub fn total_distance(&self) -> Option<i128> {
+        if self.lens[0] != self.lens[1] { return None }
+        Some(self.difference.abs())
+    }
+
+    pub fn similarity(&self) -> i128 {
+        self.similarity
+    }
+}
+
+impl FromIterator<(i64, i64)> for LocationLists {
+    fn from_iter<T: IntoIterator<Item = (i64, i64)>>(rows: T) -> Self {
+        let mut lists = LocationLists::new();
+        for (left, right) in rows {
+            lists.insert(Side::Left, left);
+            lists.insert(Side::Right, right);
+        }
+        lists
+    }
+}
+
+// All i64 IDs, the bounds of the nodes are i128 so the end of the range fits
+const DIFFERENCE_LOW: i128 = i64::MIN as i128;
+const DIFFERENCE_HIGH: i128 = i64::MAX as i128 + 1;
+
+// The difference of the counts as a function of x in [DIFFERENCE_LOW, DIFFERENCE_HIGH), as an implicit segment tree
+// A node which got +-1 lazily has only values of one sign afterwards, so the sum of absolute values stays |sum|
+// Only nodes with both signs (with 0 counted on the side of delta) are split further, see `LocationLists` for the bound
+#[derive(Debug)]
+enum Difference {
+    Uniform(i64),
+    Split {
+        min: i64,
+        max: i64,
+        sum: i128,
+        abs: i128,
+        // Still to be added to the children
+        pending: i64,
+        children: Box<[Difference; 2]>,
+    },
+}
+
+impl Default for Difference {
+    fn default() -> Self {
+        Difference::Uniform(0)
+    }
 }
 
-pub fn main_day1_task2() {
-    let (col1, col2) = get_content();
-    let mut col2_map: HashMap<i64, i64> = HashMap::new();
-    
-    for value in col2 {
-        *col2_map.entry(value).or_insert(0) += 1;
+impl Difference {
+    fn abs(&self) -> i128 {
+        self.stats(DIFFERENCE_HIGH - DIFFERENCE_LOW).3
+    }
+
+    // min, max, sum and sum of absolute values over a node of `len` integers
+    fn stats(&self, len: i128) -> (i64, i64, i128, i128) {
+        match self {
+            Difference::Uniform(d) => (*d, *d, *d as i128 * len, d.abs() as i128 * len),
+            Difference::Split { min, max, sum, abs, .. } => (*min, *max, *sum, *abs),
+        }
+    }
+
+    fn add(&mut self, from: i64, delta: i64) {
+        self.add_in(DIFFERENCE_LOW, DIFFERENCE_HIGH, from as i128, delta);
     }
-    
-    let result: i64 = col1.iter().map(|value| col2_map.get(value).unwrap_or(&0) * value).sum();
+
+    // Adds `delta` to everything from `from` on, the node covers [low, high)
+    fn add_in(&mut self, low: i128, high: i128, from: i128, delta: i64) {
+        if from >= high { return }
+        let len = high - low;
+        if from <= low {
+            match self {
+                Difference::Uniform(d) => return *d += delta,
+                Difference::Split { min, max, .. } if (delta > 0 && (*min >= 0 || *max < 0)) || (delta < 0 && (*min > 0 || *max <= 0)) => {
+                    return self.shift(delta, len)
+                },
+                _ => (),
+            }
+        }
+
+        let mid = (low + high) / 2;
+        if let Difference::Uniform(d) = *self {
+            *self = Difference::Split {
+                min: d,
+                max: d,
+                sum: d as i128 * len,
+                abs: d.abs() as i128 * len,
+                pending: 0,
+                children: Box::new([Difference::Uniform(d), Difference::Uniform(d)]),
+            };
+        }
+        let Difference::Split { min, max, sum, abs, pending, children } = self else { unreachable!() };
+        let [left, right] = children.as_mut();
+        // Only a node which got a delta has children of one sign, shifting the others by 0 would lose their abs
+        if *pending != 0 {
+            left.shift(*pending, mid - low);
+            right.shift(*pending, high - mid);
+            *pending = 0;
+        }
+
+        left.add_in(low, mid, from, delta);
+        right.add_in(mid, high, from, delta);
+
+        if let (Difference::Uniform(a), Difference::Uniform(b)) = (&*left, &*right) {
+            if a == b { return *self = Difference::Uniform(*a) }
+        }
+        let (min1, max1, sum1, abs1) = left.stats(mid - low);
+        let (min2, max2, sum2, abs2) = right.stats(high - mid);
+        (*min, *max, *sum, *abs) = (min1.min(min2), max1.max(max2), sum1 + sum2, abs1 + abs2);
+    }
+
+    // Adds `delta` to the whole node, which has only values of one sign afterwards
+    fn shift(&mut self, delta: i64, len: i128) {
+        match self {
+            Difference::Uniform(d) => *d += delta,
+            Difference::Split { min, max, sum, abs, pending, .. } => {
+                *min += delta;
+                *max += delta;
+                *sum += delta as i128 * len;
+                *abs = sum.abs();
+                *pending += delta;
+            },
+        }
+    }
+}
+
+fn get_content(ctx: &Context) -> Reconciliation {
+    let delimiter: String = ctx.param("delimiter", " ".to_string());
+    reconcile(ctx.lines(), &delimiter).unwrap_or_else(|errors| {
+        let errors = errors.iter().map(|e| e.to_string()).collect::<Vec<String>>();
+        panic!("{}", errors.join("\n"))
+    })
+}
+
+// Writes a report into the file given by the parameter `name`, if there is one
+fn export(ctx: &Context, name: &str, write: impl FnOnce(BufWriter<File>) -> io::Result<()>) {
+    let path: String = ctx.param(name, String::new());
+    if path.is_empty() { return }
+    File::create(&path)
+        .and_then(|file| write(BufWriter::new(file)))
+        .unwrap_or_else(|e| panic!("Could not write {path}: {e}"));
+}
+
+pub fn parse_input(ctx: &Context) {
+    ctx.keep_parsed(get_content(ctx));
+}
+
+pub fn main_day1_task1(ctx: &Context) {
+    let reconciliation = ctx.parsed(get_content);
+    export(ctx, "pairs_csv", |writer| reconciliation.write_pairs_csv(writer));
+    let result = reconciliation.total_distance();
+    println!("Day 1 task 1 result is {}", result);
+    ctx.answer(1, result);
+}
+
+pub fn main_day1_task2(ctx: &Context) {
+    let reconciliation = ctx.parsed(get_content);
+    export(ctx, "similarity_csv", |writer| reconciliation.write_contributions_csv(writer));
+    let result = reconciliation.similarity();
     println!("Day 1 task 2 result is {}", result);
+    ctx.answer(2, result);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use utils::random::Lcg;
+
+    const SAMPLE: [&str; 6] = ["3   4", "4   3", "2   5", "1   3", "3   9", "3   3"];
+
+    #[test]
+    fn test_reconcile() {
+        let r = reconcile(SAMPLE, " ").unwrap();
+        assert_eq!(r.total_distance(), 11);
+        assert_eq!(r.similarity(), 31);
+        assert_eq!(r.pairs[0], Pair { left: 1, right: 3, distance: 2 });
+        assert_eq!(r.largest_discrepancies(1), vec![Pair { left: 4, right: 9, distance: 5 }]);
+        assert_eq!(r.contributions[2], Contribution { id: 3, left_count: 3, right_count: 3, score: 27 });
+
+        let csv = SAMPLE.map(|line| line.replace("   ", ";"));
+        assert_eq!(reconcile(&csv, ";").unwrap(), r);
+
+        let mut out = vec![];
+        r.write_pairs_csv(&mut out).unwrap();
+        assert!(String::from_utf8(out).unwrap().starts_with("left,right,distance\n1,3,2\n"));
+
+        let extreme = reconcile([format!("{} {}", i64::MIN, i64::MAX), format!("{} {}", i64::MAX, i64::MAX)], " ").unwrap();
+        assert_eq!(extreme.total_distance(), u64::MAX as i128);
+        assert_eq!(extreme.similarity(), 2 * i64::MAX as i128);
+    }
+
+    #[test]
+    fn test_reconcile_errors() {
+        let errors = reconcile(["3,4", "4,3,1", "2,x", "1,"], ",").unwrap_err();
+        let errors = errors.iter().map(|e| e.to_string()).collect::<Vec<String>>();
+        assert_eq!(errors, vec![
+            "line 2, column 1: expected 2 columns, found 3",
+            "line 3, column 3: invalid value \"x\": invalid digit found in string",
+            "line 4, column 1: the left list has 2 IDs, the right list has 1",
+        ]);
+    }
+
+    #[test]
+    fn test_location_lists() {
+        let mut lists: LocationLists = SAMPLE
+            .iter()
+            .map(|line| line.split_once("   ").unwrap())
+            .map(|(left, right)| (left.parse().unwrap(), right.parse().unwrap()))
+            .collect();
+        assert_eq!((lists.total_distance(), lists.similarity()), (Some(11), 31));
+
+        lists.insert(Side::Left, 9);
+        assert_eq!((lists.total_distance(), lists.similarity()), (None, 40));
+        assert!(!lists.remove(Side::Right, 7));
+        assert!(lists.remove(Side::Right, 3));
+        assert_eq!((lists.len(Side::Left), lists.len(Side::Right)), (7, 5));
+
+        // Against sorting from scratch, with closely interleaved lists which grow as two of three updates insert
+        let mut left: Vec<i64> = vec![];
+        let mut right: Vec<i64> = vec![];
+        let mut lists = LocationLists::new();
+        let mut rng = Lcg::new(7);
+        let (mut longest, mut compared) = (0, 0);
+        for _ in 0..3000 {
+            let id = rng.below(50) as i64 - 25;
+            let (side, list) = if rng.below(2) == 0 { (Side::Left, &mut left) } else { (Side::Right, &mut right) };
+            if rng.below(3) != 0 || list.is_empty() {
+                lists.insert(side, id);
+                list.push(id);
+            }
+            else {
+                let removed = list.swap_remove(rng.below(list.len() as u64) as usize);
+                assert!(lists.remove(side, removed));
+            }
+            longest = longest.max(list.len());
+
+            left.sort();
+            right.sort();
+            let distance = (left.len() == right.len()).then(|| left.iter().zip(right.iter()).map(|(l, r)| (l - r).abs()).sum::<i64>());
+            let similarity: i64 = left.iter().map(|l| l * (right.partition_point(|r| r <= l) - right.partition_point(|r| r < l)) as i64).sum();
+            assert_eq!((lists.total_distance(), lists.similarity()), (distance.map(i128::from), similarity as i128));
+            compared += distance.is_some() as usize;
+        }
+        assert!(longest > 500 && compared > 10, "longest list {longest}, {compared} distances compared");
+
+        // The whole i64 range
+        let lists: LocationLists = [(i64::MIN, i64::MAX), (i64::MAX, i64::MIN), (0, -1)].into_iter().collect();
+        assert_eq!(lists.total_distance(), Some(1));
+        let lists: LocationLists = [(i64::MIN, i64::MAX), (i64::MAX, i64::MAX)].into_iter().collect();
+        assert_eq!(lists.total_distance(), Some(u64::MAX as i128));
+        assert_eq!(lists.similarity(), 2 * i64::MAX as i128);
+    }
 }
@@ -3,15 +3,14 @@ use std::collections::{HashMap, HashSet};
 use env_logger;
 use log;
 
-use utils;
+use utils::Context;
 
-const FILENAME: &str = "day10\\data.txt";
 const RADIX: u32 = 10;
 
-fn get_data() -> Vec<Vec<u32>> {
+fn get_data(ctx: &Context) -> Vec<Vec<u32>> {
     let mut height_map = vec![];
     
-    for line in utils::file_to_iter(FILENAME) {
+    for line in ctx.lines() {
         height_map.push(line.chars().map(|c| c.to_digit(RADIX).unwrap()).collect())
     }
 
@@ -122,23 +121,31 @@ fn calc_all_trails(trail_map: &Vec<HashMap<(usize, usize), Vec<(usize, usize)>>>
     trail_map[0].keys().map(|p| trail_score[p]).sum()
 }
 
-pub fn main_day10_task1() {
+pub fn parse_input(ctx: &Context) {
+    ctx.keep_parsed(get_data(ctx));
+}
+
+pub fn main_day10_task1(ctx: &Context) {
     _ = env_logger::try_init();
 
-    let height_map = get_data();
+    let height_map = ctx.parsed(get_data);
     log::debug!("{height_map:?}");
 
     let trail_map = calc_trail_map(&height_map);
-    println!("Day 10 task 1 result is {}", calc_trailhead_scores(&trail_map));
+    let result = calc_trailhead_scores(&trail_map);
+    println!("Day 10 task 1 result is {}", result);
+    ctx.answer(1, result);
     println!("Day 10 extra - all reachable tops, no duplicates is {}", calc_reachable_tops(&trail_map));
 }
 
-pub fn main_day10_task2() {
+pub fn main_day10_task2(ctx: &Context) {
     _ = env_logger::try_init();
 
-    let height_map = get_data();
+    let height_map = ctx.parsed(get_data);
     log::debug!("{height_map:?}");
 
     let trail_map = calc_trail_map(&height_map);
-    println!("Day 10 task 2 result is {}", calc_all_trails(&trail_map));
+    let result = calc_all_trails(&trail_map);
+    println!("Day 10 task 2 result is {}", result);
+    ctx.answer(2, result);
 }
@@ -3,48 +3,82 @@ use std::collections::HashMap;
 use env_logger;
 use log;
 
-use utils;
+use utils::{self, Context};
+use utils::checked::{self, Origin};
+use utils::memo::Memo;
 
-const FILENAME: &str = "day11\\data.txt";
+fn get_data(ctx: &Context) -> Vec<u64> {
+    utils::file_to_string_vector(ctx.input())[0].split(' ').map(|s| s.parse::<u64>().unwrap()).collect()
+}
+
+// All stones come from the single input line
+const ORIGIN: Origin = Origin { day: 11, line: 1 };
+
+// A stone becomes one or two stones, kept inline so remembered transformations are copied without allocating
+#[derive(Debug, Clone, Copy)]
+struct Stones {
+    values: [u64; 2],
+    len: usize,
+}
 
-fn get_data() -> Vec<u64> {
-    utils::file_to_string_vector(FILENAME)[0].split(' ').map(|s| s.parse::<u64>().unwrap()).collect()
+impl Stones {
+    fn one(stone: u64) -> Self {
+        Stones { values: [stone, 0], len: 1 }
+    }
+
+    fn two(left: u64, right: u64) -> Self {
+        Stones { values: [left, right], len: 2 }
+    }
 }
 
-fn transform(stone: u64) -> Vec<u64> {
+impl IntoIterator for Stones {
+    type Item = u64;
+    type IntoIter = std::iter::Take<std::array::IntoIter<u64, 2>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.values.into_iter().take(self.len)
+    }
+}
+
+fn transform(stone: u64) -> Stones {
     // Rule 1: 0 to 1
-    if stone == 0 { return vec![1] }
+    if stone == 0 { return Stones::one(1) }
     
     // Rule 2: split if length is even
     let stone_str = stone.to_string();
     if stone_str.len() % 2 == 0 {
-        return vec![
+        return Stones::two(
             stone_str[0..stone_str.len()/2].parse::<u64>().unwrap(),
             stone_str[stone_str.len()/2..].parse::<u64>().unwrap(),
-        ]
+        )
     }
 
     // Rule 3: multiply by 2024
-    vec![stone * 2024]
+    Stones::one(checked::mul(stone, 2024, ORIGIN))
+}
+
+pub fn parse_input(ctx: &Context) {
+    ctx.keep_parsed(get_data(ctx));
 }
 
-pub fn main_day11_task1() {
+pub fn main_day11_task1(ctx: &Context) {
     _ = env_logger::try_init();
     
-    let mut stones = get_data();
+    let mut stones = ctx.parsed(get_data);
     log::debug!("{stones:?}");
 
-    for _ in 1..=25 {
+    for _ in 1..=ctx.param("blinks_task1", 25) {
         stones = stones.iter().map(|&stone| transform(stone)).flatten().collect();
     }
 
     println!("Day 11 task 1 result is {}", stones.len());
+    ctx.answer(1, stones.len());
 }
 
-pub fn main_day11_task2() {
+pub fn main_day11_task2(ctx: &Context) {
     _ = env_logger::try_init();
     
-    let stones = get_data();
+    let stones = ctx.parsed(get_data);
     log::debug!("{stones:?}");
 
     // Stone number, number of occurrence
@@ -53,18 +87,21 @@ pub fn main_day11_task2() {
         *total_map.entry(v).or_insert(0) += 1;
     }
 
-    for _ in 1..=75 {
+    // Only a few thousand different stones show up, so their transformations are remembered
+    let mut transformations: Memo<u64, Stones> = Memo::new();
+    for _ in 1..=ctx.param("blinks_task2", 75) {
         let mut temp_map: HashMap<u64, u64> = HashMap::new();
         for (stone, occurrence) in total_map {
-            for s in transform(stone) {
-                temp_map
-                    .entry(s)
-                    .and_modify(|e| *e += occurrence)
-                    .or_insert(occurrence);
+            for s in transformations.get_or_insert_with(&stone, |_| transform(stone)) {
+                let count = temp_map.entry(s).or_insert(0);
+                *count = checked::add(*count, occurrence, ORIGIN);
             }
         }
-        total_map = temp_map.clone();
+        total_map = temp_map;
     }
+    log::debug!("{}", transformations.stats());
 
-    println!("Day 11 task 2 result is {}", total_map.values().sum::<u64>());
+    let result = checked::sum(total_map.values().map(|count| (*count, ORIGIN)));
+    println!("Day 11 task 2 result is {}", result);
+    ctx.answer(2, result);
 }
@@ -3,9 +3,8 @@ use std::collections::{HashMap, HashSet};
 use env_logger;
 use log;
 
-use utils;
-
-const FILENAME: &str = "day12\\data.txt";
+use utils::explore::{self, Explore};
+use utils::Context;
 
 #[derive(Debug, Eq, PartialEq, Hash, Copy, Clone)]
 struct Point {
@@ -137,11 +136,11 @@ impl Plot {
     }
 }
 
-fn get_garden() -> HashMap<char, HashMap<String, Plot>> {
+fn get_garden(ctx: &Context) -> HashMap<char, HashMap<String, Plot>> {
     // Use a hashmap of plots because of the merging
     let mut garden: HashMap<char, HashMap<String, Plot>> = HashMap::new();
     
-    for (i, line) in utils::file_to_iter(FILENAME).enumerate() {
+    for (i, line) in ctx.lines().enumerate() {
         for (j, c) in line.chars().enumerate() {
             let id = format!("{i}{j}");
             let new_point = Point{x: i as i32, y: j as i32};
@@ -186,10 +185,65 @@ fn get_garden() -> HashMap<char, HashMap<String, Plot>> {
     garden
 }
 
-pub fn main_day12() {
+// Looking at single regions in the REPL
+struct Explorer {
+    rows: Vec<Vec<char>>,
+    garden: HashMap<char, HashMap<String, Plot>>,
+}
+
+impl Explorer {
+    fn region(&self, x: usize, y: usize) -> Result<String, String> {
+        let plant = *self.rows.get(x).and_then(|row| row.get(y)).ok_or(format!("{x},{y} is outside of the garden"))?;
+        let point = Point { x: x as i32, y: y as i32 };
+        let plot = self.garden[&plant].values().find(|plot| plot.area.contains(&point)).unwrap();
+
+        let (area, perimeter, sides) = (plot.calc_area(), plot.calc_perimeter(), plot.calc_corners());
+        let mut lines = vec![format!(
+            "Plant {plant}: area {area}, perimeter {perimeter}, sides {sides}, price {} or {} with the discount",
+            area * perimeter, area * sides,
+        )];
+        let x_range = plot.area.iter().map(|p| p.x).min().unwrap()..=plot.area.iter().map(|p| p.x).max().unwrap();
+        let y_range = plot.area.iter().map(|p| p.y).min().unwrap()..=plot.area.iter().map(|p| p.y).max().unwrap();
+        for x in x_range {
+            lines.push(y_range
+                .clone()
+                .map(|y| if plot.area.contains(&Point { x, y }) { plant } else { '.' })
+                .collect::<String>()
+            );
+        }
+        Ok(lines.join("\n"))
+    }
+}
+
+impl Explore for Explorer {
+    fn commands(&self) -> &'static [(&'static str, &'static str)] {
+        &[("region <row> <column>", "size, price and shape of the region containing the plot")]
+    }
+
+    fn run(&mut self, command: &str, args: &[&str]) -> Result<String, String> {
+        match command {
+            "region" => self.region(
+                explore::arg(args, 0, "region <row> <column>")?,
+                explore::arg(args, 1, "region <row> <column>")?,
+            ),
+            _ => Err(format!("Unknown command: {command}")),
+        }
+    }
+}
+
+pub fn explore(ctx: &Context) -> Box<dyn Explore> {
+    let rows = ctx.lines().map(|line| line.chars().collect()).collect();
+    Box::new(Explorer { rows, garden: get_garden(ctx) })
+}
+
+pub fn parse_input(ctx: &Context) {
+    ctx.keep_parsed(get_garden(ctx));
+}
+
+pub fn main_day12(ctx: &Context) {
     _ = env_logger::try_init();
 
-    let garden = get_garden();
+    let garden = ctx.parsed(get_garden);
     for (c, plant) in garden.iter() {
         log::debug!("{:?}", c);
         for plot in plant {
@@ -208,6 +262,7 @@ pub fn main_day12() {
             .sum::<u32>()
         ).sum();
     println!("Day 12 task 1 result is {}", result);
+    ctx.answer(1, result);
 
     let result: u32 = garden
         .iter()
@@ -236,4 +291,5 @@ pub fn main_day12() {
             .sum::<u32>()
             }).sum();
     println!("Day 12 task 2 result (corners) is {}", result);
+    ctx.answer(2, result);
 }
@@ -1,16 +1,16 @@
-use itertools::Itertools;
 use lazy_static::lazy_static;
-use regex::Regex;
 use env_logger;
 use log;
 
-use utils;
+use utils::{Context, Progress};
+use utils::checked::{self, Origin};
+use utils::number_theory;
+use utils::parse::{self, Template};
 
-const FILENAME: &str = "day13\\data.txt";
 lazy_static!{
-    pub static ref REGEX_A: Regex = Regex::new(r"Button A: X\+(?<x>\d+), Y\+(?<y>\d+)").unwrap();
-    pub static ref REGEX_B: Regex = Regex::new(r"Button B: X\+(?<x>\d+), Y\+(?<y>\d+)").unwrap();
-    pub static ref REGEX_PRIZE: Regex = Regex::new(r"Prize: X=(?<x>\d+), Y=(?<y>\d+)").unwrap();
+    pub static ref BUTTON_A: Template = Template::new("Button A: X+{uint}, Y+{uint}");
+    pub static ref BUTTON_B: Template = Template::new("Button B: X+{uint}, Y+{uint}");
+    pub static ref PRIZE: Template = Template::new("Prize: X={uint}, Y={uint}");
 }
 
 #[derive(Clone, Copy, PartialEq)]
@@ -25,45 +25,48 @@ struct Button {
     cost: i64,
 }
 
-fn get_data(shift: i64) -> Vec<(Button, Button, Point)> {
+// Buttons, prize and the line number where the machine starts
+fn get_data(ctx: &Context) -> Vec<(Button, Button, Point, usize)> {
     let mut data = vec![];
-    let stream = utils::file_to_iter(FILENAME);
-
-    for lines in &stream.chunks(4) {
-        let lines: Vec<String> = lines.collect();
-        let caps1 = REGEX_A.captures(&lines[0]).unwrap();
-        let caps2 = REGEX_B.captures(&lines[1]).unwrap();
-        let caps3 = REGEX_PRIZE.captures(&lines[2]).unwrap();
-        
+    let lines: Vec<String> = ctx.lines().collect();
+
+    for block in parse::blocks(&lines) {
+        block.expect_lines(3).unwrap();
+        let caps1 = BUTTON_A.parse::<i64>(block.field(0).unwrap()).unwrap();
+        let caps2 = BUTTON_B.parse::<i64>(block.field(1).unwrap()).unwrap();
+        let caps3 = PRIZE.parse::<i64>(block.field(2).unwrap()).unwrap();
+
         data.push((
             Button{
             p: Point{
-                x: caps1["x"].parse::<i64>().unwrap(),
-                y: caps1["y"].parse::<i64>().unwrap(),
+                x: caps1[0],
+                y: caps1[1],
             },
             cost: 3,
             },
             Button{
                 p: Point{
-                    x: caps2["x"].parse::<i64>().unwrap(),
-                    y: caps2["y"].parse::<i64>().unwrap(),
+                    x: caps2[0],
+                    y: caps2[1],
                 },
                 cost: 1,
             },
             Point {
-                x: caps3["x"].parse::<i64>().unwrap() + shift,
-                y: caps3["y"].parse::<i64>().unwrap() + shift,
-            }
+                x: caps3[0],
+                y: caps3[1],
+            },
+            block.line,
         ))
     }
 
     data
 }
 
-fn calculate_cheapest(button1: &Button, button2: &Button, prize: &Point) -> Option<i64> {
+fn calculate_cheapest(button1: &Button, button2: &Button, prize: &Point, progress: &Progress) -> Option<i64> {
     let mut min_cost: Option<i64> = None;
 
     for i in 0.. {
+        if progress.is_cancelled() { return None }
         let remainder_x = prize.x - i * button1.p.x;
         if remainder_x < 0 { break }
         let remainder_y = prize.y - i * button1.p.y;
@@ -82,37 +85,54 @@ fn calculate_cheapest(button1: &Button, button2: &Button, prize: &Point) -> Opti
     min_cost
 }
 
-fn closed_form(button1: &Button, button2: &Button, prize: &Point) -> Option<i64> {
-    let numerator = button1.p.x * prize.y - button1.p.y * prize.x;
-    let denominator = button1.p.x * button2.p.y - button1.p.y * button2.p.x;
-    match numerator % denominator {
-        0 => {
-            let b = numerator / denominator;
-            let a = (prize.x - b * button2.p.x) / button1.p.x;
-            Some(a * button1.cost + b * button2.cost)
-        },
-        _ => None
-    }
+// The cost stays an i128, it is not cut down to i64
+fn closed_form(button1: &Button, button2: &Button, prize: &Point) -> Option<i128> {
+    let [a, b] = number_theory::solve_2x2(
+        [[button1.p.x as i128, button2.p.x as i128], [button1.p.y as i128, button2.p.y as i128]],
+        [prize.x as i128, prize.y as i128],
+    )?;
+    // Only whole, non negative numbers of button presses count
+    let (a, b) = (a.integer()?, b.integer()?);
+    if a < 0 || b < 0 { return None }
+    Some(a * button1.cost as i128 + b * button2.cost as i128)
 }
 
-pub fn main_day13_task1() {
+// Task 2 moves every prize this far in both directions
+const SHIFT: i64 = 10000000000000;
+
+pub fn parse_input(ctx: &Context) {
+    ctx.keep_parsed(get_data(ctx));
+}
+
+pub fn main_day13_task1(ctx: &Context) {
     _ = env_logger::try_init();
 
-    let result: i64 = get_data(0)
+    let machines = ctx.parsed(get_data);
+    let result: i64 = machines
         .iter()
-        .map(|(button1, button2, prize)| calculate_cheapest(button1, button2, prize))
-        .flatten()
+        .enumerate()
+        .filter_map(|(i, (button1, button2, prize, _))| {
+            ctx.progress().report(i as u64, Some(machines.len() as u64));
+            calculate_cheapest(button1, button2, prize, ctx.progress())
+        })
         .sum();
+    if ctx.progress().was_cancelled() { return }
     println!("Day 13 task 1 result is {}", result);
+    ctx.answer(1, result);
 }
 
-pub fn main_day13_task2() {
+pub fn main_day13_task2(ctx: &Context) {
     _ = env_logger::try_init();
 
-    let result: i64 = get_data(10000000000000)
+    let result: i128 = checked::sum(ctx.parsed(get_data)
         .iter()
-        .map(|(button1, button2, prize)| closed_form(button1, button2, prize))
-        .flatten()
-        .sum();
+        .filter_map(|(button1, button2, prize, line)| {
+            // The prize is on the third line of the machine
+            let origin = Origin::new(13, line + 2);
+            let prize = Point { x: checked::add(prize.x, SHIFT, origin), y: checked::add(prize.y, SHIFT, origin) };
+            closed_form(button1, button2, &prize).map(|cost| (cost, Origin::new(13, *line)))
+        })
+    );
     println!("Day 13 task 2 result is {}", result);
+    ctx.answer(2, result);
 }
@@ -1,17 +1,17 @@
 use std::collections::HashMap;
-use std::io::stdin;
 
 use env_logger;
 use lazy_static::lazy_static;
 use log;
-use regex::Regex;
 
-use utils;
+use utils::Context;
+use utils::simulation::Simulation;
+use utils::number_theory;
+use utils::parse::{self, Template};
 
-const FILENAME: &str = "day14\\data.txt";
 lazy_static!{
     //p=0,4 v=3,-3
-    pub static ref RE: Regex = Regex::new(r"p=(?<x>\d+),(?<y>\d+) v=(?<vx>-?\d+),(?<vy>-?\d+)").unwrap();
+    pub static ref ROBOT: Template = Template::new("p={uint},{uint} v={int},{int}");
 }
 
 #[derive(Debug)]
@@ -39,29 +39,65 @@ fn get_quadrant(robot: &Robot, dim_x: i64, dim_y: i64) -> i64 {
     0
 }
 
-fn get_data() -> Vec<Robot> {
-    utils::file_to_iter(FILENAME).map(|s| {
-        let caps = RE.captures(&s).unwrap();
-        Robot{
-            x: caps["x"].parse::<i64>().unwrap(),
-            y: caps["y"].parse::<i64>().unwrap(),
-            vx: caps["vx"].parse::<i64>().unwrap(),
-            vy: caps["vy"].parse::<i64>().unwrap(),
+// Sum of the squared distances from the mean of one coordinate
+fn spread(robots: &[Robot], coordinate: fn(&Robot) -> i64) -> i64 {
+    let values: Vec<i64> = robots.iter().map(coordinate).collect();
+    let mean = values.iter().sum::<i64>() / values.len() as i64;
+    values.iter().map(|v| (v - mean) * (v - mean)).sum()
+}
+
+// All robots moving on the grid, one second per step
+struct Bathroom {
+    robots: Vec<Robot>,
+    dim_x: i64,
+    dim_y: i64,
+}
+
+impl Simulation for Bathroom {
+    // The velocities never change
+    type State = Vec<(i64, i64)>;
+
+    fn step(&mut self) -> bool {
+        for robot in self.robots.iter_mut() {
+            *robot = move_robot(robot, 1, self.dim_x, self.dim_y);
         }
-    }).collect::<Vec<Robot>>()
+        true
+    }
+
+    fn state(&self) -> Self::State {
+        self.robots.iter().map(|robot| (robot.x, robot.y)).collect()
+    }
+}
+
+fn get_data(ctx: &Context) -> Vec<Robot> {
+    parse::lines(ctx.lines(), |line| {
+        let values = ROBOT.parse::<i64>(line)?;
+        Ok(Robot{
+            x: values[0],
+            y: values[1],
+            vx: values[2],
+            vy: values[3],
+        })
+    }).unwrap()
+}
+
+pub fn parse_input(ctx: &Context) {
+    ctx.keep_parsed(get_data(ctx));
 }
 
-pub fn main_day14_task1() {
+pub fn main_day14_task1(ctx: &Context) {
     _ = env_logger::try_init();
 
-    let robots = get_data();
+    let robots = ctx.parsed(get_data);
+
+    let dim_x = ctx.param("width", 101);
+    let dim_y = ctx.param("height", 103);
 
-    let dim_x = 101;
-    let dim_y = 103;
+    let mut sim = Bathroom { robots, dim_x, dim_y };
+    sim.run(Some(ctx.param("seconds", 100)));
 
     let mut quadrants: HashMap<i64, u64> = HashMap::new();
-    for robot in robots {
-        let robot = move_robot(&robot, 100, dim_x, dim_y);
+    for robot in sim.robots {
         let quadrant = get_quadrant(&robot, dim_x, dim_y);
         *quadrants.entry(quadrant).or_insert(0) += 1;
         log::debug!("{} {}", robot.x, robot.y);
@@ -76,43 +112,44 @@ pub fn main_day14_task1() {
         * quadrants.get(&3).unwrap_or(&0)
         * quadrants.get(&4).unwrap_or(&0);
     println!("Day 14 task 1 result is {}", result);
+    ctx.answer(1, result);
 }
 
-pub fn main_day14_task2() {
+pub fn main_day14_task2(ctx: &Context) {
     _ = env_logger::try_init();
 
-    let robots = get_data();
-
-    let dim_x = 101;
-    let dim_y = 103;
-
-    for i in 0..10000000 {
-        let mut state = vec![vec![0; dim_x]; dim_y];
-        for robot in robots.iter() {
-            let new_robot = move_robot(&robot, i, dim_x as i64, dim_y as i64);
-            state[new_robot.y as usize][new_robot.x as usize] += 1;
-        }
-
-        let mut total_x = 0;
-        let mut total_y = 0;
-        for (u, y) in state.iter().enumerate() {
-            for (v, x) in y.iter().enumerate() {
-                if v > 40 && v < 60 && *x > 0 { total_x += 1; }
-                if u > 40 && u < 60 && *x > 0 { total_y += 1; }
-            }
-        }
-
-        if total_x > 200 && total_y > 150 {
-            println!("##############################{i}##############################");
-            for y in state.iter() {
-                for x in y.iter() {
-                    if *x > 0 { print!("*"); }
-                    else { print!(" "); }
-                }
-                println!();
-            }
-            let mut s=String::new();
-            stdin().read_line(&mut s).expect("Did not enter a correct string");
-        }
+    let robots = ctx.parsed(get_data);
+
+    let dim_x: i64 = ctx.param("width", 101);
+    let dim_y: i64 = ctx.param("height", 103);
+
+    // In the picture most robots are close together, both in x and in y
+    // The x positions repeat every dim_x seconds and the y positions every dim_y, so take the most clustered
+    // time of each and combine them
+    let steps = dim_x.max(dim_y) - 1;
+    let mut spreads = vec![];
+    let mut sim = Bathroom { robots, dim_x, dim_y };
+    sim.run_until(|sim| {
+        spreads.push((spread(&sim.robots, |r| r.x), spread(&sim.robots, |r| r.y)));
+        false
+    }, Some(steps as usize));
+    let t_x = (0..dim_x).min_by_key(|&t| spreads[t as usize].0).unwrap();
+    let t_y = (0..dim_y).min_by_key(|&t| spreads[t as usize].1).unwrap();
+    let (result, _) = number_theory::crt(&[(t_x as i128, dim_x as i128), (t_y as i128, dim_y as i128)]).unwrap();
+    let result = result as i64;
+
+    // The picture is far ahead, the robots jump there instead of stepping
+    let mut state = vec![vec![' '; dim_x as usize]; dim_y as usize];
+    for robot in sim.robots.iter() {
+        let new_robot = move_robot(robot, result - steps, dim_x, dim_y);
+        state[new_robot.y as usize][new_robot.x as usize] = '*';
     }
+    let picture: Vec<String> = state.iter().map(|row| row.iter().collect()).collect();
+    for row in picture.iter() {
+        println!("{row}");
+    }
+
+    println!("Day 14 task 2 result is {}", result);
+    ctx.answer(2, result);
+    ctx.grid("Easter egg", picture);
 }
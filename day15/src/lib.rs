@@ -3,9 +3,8 @@ use std::collections::HashSet;
 use env_logger;
 use log;
 
-use utils;
+use utils::{parse, simulation::Simulation, Context};
 
-const FILENAME: &str = "day15\\data.txt";
 const BOX: char = 'O';
 const BOX_L: char = '[';
 const BOX_R: char = ']';
@@ -132,23 +131,12 @@ struct Point {
     y: usize,
 }
 
-fn get_data() -> (Vec<Vec<char>>, Vec<char>) {
-    let mut warehouse = vec![];
-    let mut movement = vec![];
-    let mut warehouse_done = false;
-    
-    for line in utils::file_to_iter(FILENAME) {
-        if !line.is_empty() && !warehouse_done {
-            warehouse.push(line.chars().collect::<Vec<char>>());
-            continue
-        }
-        else if !warehouse_done {
-            warehouse_done = true;
-            continue
-        }
+fn get_data(ctx: &Context) -> (Vec<Vec<char>>, Vec<char>) {
+    let lines: Vec<String> = ctx.lines().collect();
+    let sections = parse::sections(&lines, &["warehouse", "moves"]).unwrap();
 
-        movement.extend(line.chars());
-    }
+    let warehouse = sections.get("warehouse").fields().map(|line| line.chars().collect()).collect();
+    let movement = sections.get("moves").fields().flat_map(|line| line.as_str().chars()).collect();
 
     (warehouse, movement)
 }
@@ -221,8 +209,9 @@ fn get_walls(warehouse: &Vec<Vec<char>>) -> HashSet<Point> {
     walls
 }
 
-fn render_wide_warehouse(dim_x: usize, dim_y: usize, robot: Point, boxes: &HashSet<Point>, walls: &HashSet<Point>) {
+fn wide_warehouse_rows(dim_x: usize, dim_y: usize, robot: Point, boxes: &HashSet<Point>, walls: &HashSet<Point>) -> Vec<String> {
     let mut jump = false;
+    let mut rows = vec![];
 
     for i in 0..dim_x {
         let mut to_print = vec!['.'; dim_y];
@@ -238,59 +227,130 @@ fn render_wide_warehouse(dim_x: usize, dim_y: usize, robot: Point, boxes: &HashS
                 else { to_print[j] = EMPTY; }
             }
         }
-        log::debug!("{:?}", to_print.iter().collect::<String>());
+        rows.push(to_print.iter().collect::<String>());
     }
+
+    rows
 }
 
-pub fn main_day15_task1() {
-    _ = env_logger::try_init();
+fn render_wide_warehouse(dim_x: usize, dim_y: usize, robot: Point, boxes: &HashSet<Point>, walls: &HashSet<Point>) {
+    for row in wide_warehouse_rows(dim_x, dim_y, robot, boxes, walls) {
+        log::debug!("{:?}", row);
+    }
+}
 
-    let (mut warehouse, movement) = get_data();
-    let mut robot = get_robot(&warehouse);
-    log::debug!("{:?}", robot);
+// The robot of task 1 following its moves, one move per step
+struct Warehouse {
+    grid: Vec<Vec<char>>,
+    robot: Point,
+    movement: Vec<char>,
+    next: usize,
+}
+
+impl Simulation for Warehouse {
+    type State = (Vec<Vec<char>>, usize);
+
+    fn step(&mut self) -> bool {
+        if self.next >= self.movement.len() { return false }
+        let direction = Direction::from(self.movement[self.next]);
+        self.next += 1;
 
-    for c in movement.iter() {
-        let direction = Direction::from(*c);
-        let outcome = move_boxes(&warehouse, &robot, direction);
+        let outcome = move_boxes(&self.grid, &self.robot, direction);
         match outcome {
             None => (),
             Some(p) => {
-                warehouse[robot.x][robot.y] = '.';
-                direction.move_point(&mut robot);
-                warehouse[robot.x][robot.y] = ROBOT;
-                if p.x != robot.x || p.y != robot.y { warehouse[p.x][p.y] = BOX; }
+                self.grid[self.robot.x][self.robot.y] = '.';
+                direction.move_point(&mut self.robot);
+                self.grid[self.robot.x][self.robot.y] = ROBOT;
+                if p.x != self.robot.x || p.y != self.robot.y { self.grid[p.x][p.y] = BOX; }
             }
         }
 
-        for row in warehouse.iter() {
+        for row in self.grid.iter() {
             log::debug!("{:?}", row.iter().collect::<String>());
         }
+        true
     }
 
+    fn state(&self) -> Self::State {
+        (self.grid.clone(), self.next)
+    }
+}
+
+// The robot of task 2 in the doubled warehouse, boxes are stored by their left half
+struct WideWarehouse {
+    robot: Point,
+    boxes: HashSet<Point>,
+    walls: HashSet<Point>,
+    dim: (usize, usize),
+    movement: Vec<char>,
+    next: usize,
+}
+
+impl Simulation for WideWarehouse {
+    type State = (Point, Vec<(usize, usize)>, usize);
+
+    fn step(&mut self) -> bool {
+        if self.next >= self.movement.len() { return false }
+        match Direction::from(self.movement[self.next]) {
+            Direction::Left => move_left(&mut self.robot, &mut self.boxes, &self.walls),
+            Direction::Right => move_right(&mut self.robot, &mut self.boxes, &self.walls),
+            d => move_vertical(&mut self.robot, &mut self.boxes, &self.walls, d),
+        }
+        self.next += 1;
+
+        render_wide_warehouse(self.dim.0, self.dim.1, self.robot, &self.boxes, &self.walls);
+        true
+    }
+
+    fn state(&self) -> Self::State {
+        let mut boxes: Vec<(usize, usize)> = self.boxes.iter().map(|b| (b.x, b.y)).collect();
+        boxes.sort();
+        (self.robot, boxes, self.next)
+    }
+}
+
+pub fn parse_input(ctx: &Context) {
+    let (warehouse, movement) = get_data(ctx);
+    get_robot(&warehouse);
+    movement.iter().for_each(|c| { Direction::from(*c); });
+    ctx.keep_parsed((warehouse, movement));
+}
+
+pub fn main_day15_task1(ctx: &Context) {
+    _ = env_logger::try_init();
+
+    let (warehouse, movement) = ctx.parsed(get_data);
+    let robot = get_robot(&warehouse);
+    log::debug!("{:?}", robot);
+
+    let mut sim = Warehouse { grid: warehouse, robot, movement, next: 0 };
+    sim.run(None);
+    let warehouse = sim.grid;
+
     let result = gps(&warehouse);
     println!("Day 15 task 1 result is {}", result);
+    ctx.answer(1, result);
+    ctx.grid("Warehouse", warehouse.iter().map(|row| row.iter().collect()));
 }
 
-pub fn main_day15_task2() {
+pub fn main_day15_task2(ctx: &Context) {
     _ = env_logger::try_init();
 
-    let (warehouse, movement) = get_data();
+    let (warehouse, movement) = ctx.parsed(get_data);
     let mut robot = get_robot(&warehouse);
     robot.y *= 2;
-    let mut boxes = get_boxes(&warehouse);
+    let boxes = get_boxes(&warehouse);
     let walls = get_walls(&warehouse);
     log::debug!("{:?}", robot);
 
-    render_wide_warehouse(warehouse.len(), warehouse[0].len() * 2, robot, &boxes, &walls);
-    for c in movement.iter() {
-        match Direction::from(*c) {
-            Direction::Left => move_left(&mut robot, &mut boxes, &walls),
-            Direction::Right => move_right(&mut robot, &mut boxes, &walls),
-            d => move_vertical(&mut robot, &mut boxes, &walls, d),
-        }
-        render_wide_warehouse(warehouse.len(), warehouse[0].len() * 2, robot, &boxes, &walls);
-    }
+    let dim = (warehouse.len(), warehouse[0].len() * 2);
+    render_wide_warehouse(dim.0, dim.1, robot, &boxes, &walls);
+    let mut sim = WideWarehouse { robot, boxes, walls, dim, movement, next: 0 };
+    sim.run(None);
 
-    let result = gps2(&boxes);
+    let result = gps2(&sim.boxes);
     println!("Day 15 task 2 result is {}", result);
+    ctx.answer(2, result);
+    ctx.grid("Wide warehouse", wide_warehouse_rows(dim.0, dim.1, sim.robot, &sim.boxes, &sim.walls));
 }
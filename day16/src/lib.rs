@@ -5,9 +5,8 @@ use env_logger;
 use log;
 use priority_queue::PriorityQueue;
 
-use utils;
+use utils::Context;
 
-const FILENAME: &str = "day16\\data.txt";
 const WALL: char = '#';
 const END: char = 'E';
 const START: char = 'S';
@@ -83,14 +82,14 @@ struct StepWithPath {
     path: String,
 }
 
-fn get_maze() -> (Point, Point, HashSet<Point>) {
+fn get_maze(ctx: &Context) -> (Point, Point, HashSet<Point>) {
     // x from top to bottom (N->S)
     // y from left to right (W->E)
     let mut walls: HashSet<Point> = HashSet::new();
     let mut start = Point { x: 0, y: 0};
     let mut end = Point { x: 0, y: 0};
     
-    for (i, line) in utils::file_to_iter(FILENAME).enumerate() {
+    for (i, line) in ctx.lines().enumerate() {
         for (j, c) in line.chars().enumerate() {
             let p = Point { x: i, y: j};
             match c {
@@ -167,10 +166,14 @@ fn step_one(step: &Step, score: u32, direction: Direction, walls: &HashSet<Point
     }
 }
 
-pub fn main_day16_task1() {
+pub fn parse_input(ctx: &Context) {
+    ctx.keep_parsed(get_maze(ctx));
+}
+
+pub fn main_day16_task1(ctx: &Context) {
     _ = env_logger::try_init();
 
-    let (start, end, walls) = get_maze();
+    let (start, end, walls) = ctx.parsed(get_maze);
     log::debug!("{:?}", start);
     log::debug!("{:?}", end);
     // A reverse priority queue because we want the lowest score
@@ -224,12 +227,13 @@ pub fn main_day16_task1() {
     };
 
     println!("Day 16 task 1 result is {}", minimum_score);
+    ctx.answer(1, minimum_score);
 }
 
-pub fn main_day16_task2() {
+pub fn main_day16_task2(ctx: &Context) {
     _ = env_logger::try_init();
 
-    let (start, end, walls) = get_maze();
+    let (start, end, walls) = ctx.parsed(get_maze);
     log::debug!("{:?}", start);
     log::debug!("{:?}", end);
     // A reverse priority queue because we want the lowest score
@@ -295,4 +299,20 @@ pub fn main_day16_task2() {
 
     println!("Day 16 task 1 result is {}", minimum_score);
     println!("Day 16 task 2 result is {}", tiles.len());
+    ctx.answer(2, tiles.len());
+
+    // Walls and the tiles on any of the best paths
+    let dim_x = walls.iter().map(|p| p.x).max().unwrap() + 1;
+    let dim_y = walls.iter().map(|p| p.y).max().unwrap() + 1;
+    ctx.grid("Best paths", (0..dim_x).map(|x| (0..dim_y)
+        .map(|y| {
+            let p = Point { x, y };
+            if p == start { START }
+            else if p == end { END }
+            else if walls.contains(&p) { WALL }
+            else if tiles.contains(&p) { 'O' }
+            else { '.' }
+        })
+        .collect::<String>()
+    ));
 }
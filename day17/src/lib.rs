@@ -1,17 +1,8 @@
 use env_logger;
-use lazy_static::lazy_static;
 use log;
-use regex::Regex;
 
-use utils;
-
-const FILENAME: &str = "day17\\data_identical.txt";
-lazy_static!{
-    pub static ref REGEX_A: Regex = Regex::new(r"Register A: (?<nr>\d+)").unwrap();
-    pub static ref REGEX_B: Regex = Regex::new(r"Register B: (?<nr>\d+)").unwrap();
-    pub static ref REGEX_C: Regex = Regex::new(r"Register C: (?<nr>\d+)").unwrap();
-    pub static ref PROGRAM: Regex = Regex::new(r"Program: (?<program>.+)").unwrap();
-}
+use utils::{explore::{self, Explore}, simulation::{Outcome, Simulation}, Context};
+use utils::parse::{self, Field};
 
 struct Computer {
     reg_a: u64,
@@ -64,51 +55,144 @@ impl Computer {
     }
 }
 
-fn get_data() -> (u64, u64, u64, Vec<u64>) {
+struct Machine {
+    computer: Computer,
+    prog: Vec<u64>,
+    output: Vec<u64>,
+}
+
+impl Machine {
+    fn output_string(&self) -> String {
+        self.output.iter().map(|v| v.to_string()).collect::<Vec<String>>().join(",")
+    }
+}
+
+impl Simulation for Machine {
+    // The output only grows, so it is left out
+    type State = (u64, u64, u64, usize);
+
+    fn step(&mut self) -> bool {
+        if self.computer.pointer >= self.prog.len() { return false }
+        let opcode = self.prog[self.computer.pointer];
+        let operand = self.prog[self.computer.pointer + 1];
+        if let Some(v) = self.computer.calc(opcode, operand) { self.output.push(v); }
+        true
+    }
+
+    fn state(&self) -> Self::State {
+        (self.computer.reg_a, self.computer.reg_b, self.computer.reg_c, self.computer.pointer)
+    }
+}
+
+// Runs the program until it halts, the output is joined with ','
+fn run_program(computer: Computer, prog: &[u64]) -> String {
+    let mut machine = Machine { computer, prog: prog.to_vec(), output: vec![] };
+    if let Outcome::Cycle { start, length } = machine.find_cycle(None) {
+        panic!("The program never halts, it repeats every {length} instructions after {start}");
+    }
+    machine.output_string()
+}
+
+fn get_data(ctx: &Context) -> (u64, u64, u64, Vec<u64>) {
     let mut reg_a: u64 = 0;
     let mut reg_b: u64 = 0;
     let mut reg_c: u64 = 0;
     let mut prog: Vec<u64> = vec![];
     
-    for line in utils::file_to_iter(FILENAME) {
-        if let Some(caps) = REGEX_A.captures(&line) {
-            reg_a = caps["nr"].parse::<u64>().unwrap();
-        }
-        if let Some(caps) = REGEX_B.captures(&line) {
-            reg_b = caps["nr"].parse::<u64>().unwrap();
+    parse::lines(ctx.lines(), |line: Field| {
+        if line.trim().is_empty() { return Ok(()) }
+        let (key, value) = line.key_value(":")?;
+        match key.as_str() {
+            "Register A" => reg_a = value.parse()?,
+            "Register B" => reg_b = value.parse()?,
+            "Register C" => reg_c = value.parse()?,
+            "Program" => prog = value.list(",")?,
+            _ => return Err(key.error(format!("unknown entry {:?}", key.as_str()))),
         }
-        if let Some(caps) = REGEX_C.captures(&line) {
-            reg_c = caps["nr"].parse::<u64>().unwrap();
-        }
-        if let Some(caps) = PROGRAM.captures(&line) {
-            prog = caps["program"].split(',').map(|c| c.parse::<u64>().unwrap()).collect();
+        Ok(())
+    }).unwrap();
+
+    (reg_a, reg_b, reg_c, prog)
+}
+
+// Stepping through the program in the REPL
+struct Explorer {
+    registers: (u64, u64, u64),
+    machine: Machine,
+}
+
+impl Explorer {
+    fn reset(&mut self, reg_a: u64) {
+        let (_, reg_b, reg_c) = self.registers;
+        self.machine.computer = Computer { reg_a, reg_b, reg_c, pointer: 0 };
+        self.machine.output.clear();
+    }
+
+    fn state(&self) -> String {
+        let Computer { reg_a, reg_b, reg_c, pointer } = self.machine.computer;
+        let next = match self.machine.prog.get(pointer..pointer + 2) {
+            Some(instruction) => format!("{},{}", instruction[0], instruction[1]),
+            None => "halted".to_string(),
+        };
+        format!("A={reg_a} B={reg_b} C={reg_c} pointer={pointer} next={next} output={}", self.machine.output_string())
+    }
+}
+
+impl Explore for Explorer {
+    fn commands(&self) -> &'static [(&'static str, &'static str)] {
+        &[
+            ("state", "registers, pointer, next instruction and output"),
+            ("step [n]", "execute n instructions, 1 by default"),
+            ("finish", "execute until the program halts"),
+            ("reset [a]", "start over, with register A set to a if given"),
+        ]
+    }
+
+    fn run(&mut self, command: &str, args: &[&str]) -> Result<String, String> {
+        match command {
+            "state" => (),
+            "step" => {
+                let n = if args.is_empty() { 1 } else { explore::arg(args, 0, "step [n]")? };
+                self.machine.run(Some(n));
+            },
+            "finish" => {
+                if let Outcome::Cycle { start, length } = self.machine.find_cycle(None) {
+                    return Err(format!("The program never halts, it repeats every {length} instructions after {start}"))
+                }
+            },
+            "reset" => {
+                let reg_a = if args.is_empty() { self.registers.0 } else { explore::arg(args, 0, "reset [a]")? };
+                self.reset(reg_a);
+            },
+            _ => return Err(format!("Unknown command: {command}")),
         }
+        Ok(self.state())
     }
+}
 
-    (reg_a, reg_b, reg_c, prog)
+pub fn explore(ctx: &Context) -> Box<dyn Explore> {
+    let (reg_a, reg_b, reg_c, prog) = get_data(ctx);
+    let machine = Machine { computer: Computer { reg_a, reg_b, reg_c, pointer: 0 }, prog, output: vec![] };
+    Box::new(Explorer { registers: (reg_a, reg_b, reg_c), machine })
 }
 
-pub fn main_day17_task1() {
+pub fn parse_input(ctx: &Context) {
+    ctx.keep_parsed(get_data(ctx));
+}
+
+pub fn main_day17_task1(ctx: &Context) {
     _ = env_logger::try_init();
 
-    let (reg_a, reg_b, reg_c, prog) = get_data();
+    let (reg_a, reg_b, reg_c, prog) = ctx.parsed(get_data);
     log::debug!("{reg_a} {reg_b} {reg_c} {:?}", prog);
-    let mut computer = Computer { reg_a, reg_b, reg_c, pointer: 0 };
-    let mut result: Vec<u64> = vec![];
-    let mut opcode: u64;
-    let mut operand: u64;
-
-    loop {
-        if computer.pointer >= prog.len() { break }
-        opcode = prog[computer.pointer];
-        operand = prog[computer.pointer + 1];
-        if let Some(v) = computer.calc(opcode, operand) { result.push(v); }
-    }
+    let computer = Computer { reg_a, reg_b, reg_c, pointer: 0 };
 
-    println!("Day 17 task 1 result is {}", result.iter().map(|v| v.to_string()).collect::<Vec<String>>().join(","));
+    let result = run_program(computer, &prog);
+    println!("Day 17 task 1 result is {}", result);
+    ctx.answer(1, result);
 }
 
-pub fn main_day17_task2() {
+pub fn main_day17_task2(ctx: &Context) {
     // Reverse engineering with pen and paper, that
     //   C has to be small, either 0 or 1 - it turned out that 1 is already too big due to the 7,5 sequence, which is C = A / 2.pow(B)
     //   B has to be small as well, due to the output of 0, it should be 0
@@ -122,26 +206,16 @@ pub fn main_day17_task2() {
 
     _ = env_logger::try_init();
 
-    let (reg_a, reg_b, reg_c, prog) = get_data();
+    let (reg_a, reg_b, reg_c, prog) = ctx.parsed(get_data);
     log::debug!("{reg_a} {reg_b} {reg_c} {:?}", prog);
-    let mut computer = Computer { reg_a, reg_b, reg_c, pointer: 0 };
-    let mut opcode: u64;
-    let mut operand: u64;
 
     for i in 202367025818154_u64..202367025818155 {
-        let mut result: Vec<u64> = vec![];
+        let computer = Computer { reg_a, reg_b, reg_c, pointer: 0 };
         //computer.reg_a = i;
-        //computer.pointer = 0;
-        loop {
-            if computer.pointer >= prog.len() { break }
-            opcode = prog[computer.pointer];
-            operand = prog[computer.pointer + 1];
-            if let Some(v) = computer.calc(opcode, operand) { result.push(v); }
-        }
-
-        let result = result.iter().map(|v| v.to_string()).collect::<Vec<String>>().join(",");
+        let result = run_program(computer, &prog);
         if result == "2,4,1,1,7,5,4,7,1,4,0,3,5,5,3,0" {
             println!("Day 17 task 1 result is i={i} {}", result);
+            ctx.answer(2, i);
         }
     }
 }
@@ -4,17 +4,17 @@ use std::cmp::Reverse;
 use env_logger;
 use log;
 use priority_queue::PriorityQueue;
-use regex::Regex;
 use lazy_static::lazy_static;
 
-use utils;
+use utils::Context;
+use utils::parse::{self, Template};
 
-const FILENAME: &str = "day18\\data.txt";
+// Defaults for the real input, the parameters "width", "height" and "take_first" override them
 const X_DIM: usize = 71;
 const Y_DIM: usize = 71;
 const TAKE_FIRST: usize = 1024;
 lazy_static!{
-    pub static ref BYTES: Regex = Regex::new(r"(?<x>\d+),(?<y>\d+)").unwrap();
+    pub static ref BYTES: Template = Template::new("{uint},{uint}");
 }
 
 #[derive(Debug, Eq, PartialEq, Hash, Copy, Clone)]
@@ -24,28 +24,16 @@ struct Point {
 }
 
 
-fn get_walls(take_first: usize) -> HashSet<Point> {
-    utils::file_to_iter(FILENAME)
-        .take(take_first)
-        .map(|line| {
-            let caps = BYTES.captures(&line).unwrap();
-            Point { x: caps["x"].parse::<usize>().unwrap(), y: caps["y"].parse::<usize>().unwrap() }
-        })
-        .collect::<HashSet<Point>>()
+fn get_all_walls(ctx: &Context) -> Vec<Point> {
+    parse::lines(ctx.lines(), |line| {
+        let values = BYTES.parse::<usize>(line)?;
+        Ok(Point { x: values[0], y: values[1] })
+    }).unwrap()
 }
 
-fn get_all_walls() -> Vec<Point> {
-    utils::file_to_iter(FILENAME)
-        .map(|line| {
-            let caps = BYTES.captures(&line).unwrap();
-            Point { x: caps["x"].parse::<usize>().unwrap(), y: caps["y"].parse::<usize>().unwrap() }
-        })
-        .collect::<Vec<Point>>()
-}
-
-fn render_maze(maze: &HashSet<Point>) {
-    for y in 0..Y_DIM {
-        for x in 0..X_DIM {
+fn render_maze(maze: &HashSet<Point>, x_dim: usize, y_dim: usize) {
+    for y in 0..y_dim {
+        for x in 0..x_dim {
             match maze.contains(&Point { x, y }) {
                 true => print!("#"),
                 false => print!("."),
@@ -55,8 +43,8 @@ fn render_maze(maze: &HashSet<Point>) {
     }
 }
 
-fn solve_maze(maze: &HashSet<Point>) -> Option<usize> {
-    let end = Point { x: X_DIM - 1, y: Y_DIM - 1 };
+fn solve_maze(maze: &HashSet<Point>, x_dim: usize, y_dim: usize) -> Option<usize> {
+    let end = Point { x: x_dim - 1, y: y_dim - 1 };
     let mut pq: PriorityQueue<Point, Reverse<usize>> = PriorityQueue::new();
     let mut min_scores: HashMap<Point, usize> = HashMap::new();
     pq.push_increase(Point{x:0, y:0}, Reverse(1));
@@ -77,7 +65,7 @@ fn solve_maze(maze: &HashSet<Point>) -> Option<usize> {
             }
         }
         // Right
-        if pos.x < X_DIM - 1{
+        if pos.x < x_dim - 1{
             let p = Point { x: pos.x + 1, y: pos.y};
             if !maze.contains(&p) && score.0 + 1 < *min_scores.entry(p).or_insert(std::usize::MAX) {
                 pq.push_increase(p, Reverse(score.0 + 1));
@@ -93,7 +81,7 @@ fn solve_maze(maze: &HashSet<Point>) -> Option<usize> {
             }
         }
         // Down
-        if pos.y < Y_DIM - 1 {
+        if pos.y < y_dim - 1 {
             let p = Point { x: pos.x, y: pos.y + 1};
             if !maze.contains(&p) && score.0 + 1 < *min_scores.entry(p).or_insert(std::usize::MAX) {
                 pq.push_increase(p, Reverse(score.0 + 1));
@@ -103,32 +91,46 @@ fn solve_maze(maze: &HashSet<Point>) -> Option<usize> {
     }
 }
 
-pub fn main_day18_task1() {
+pub fn parse_input(ctx: &Context) {
+    ctx.keep_parsed(get_all_walls(ctx));
+}
+
+pub fn main_day18_task1(ctx: &Context) {
     _ = env_logger::try_init();
 
-    let maze = get_walls(TAKE_FIRST);
-    render_maze(&maze);
+    let x_dim = ctx.param("width", X_DIM);
+    let y_dim = ctx.param("height", Y_DIM);
+    let maze = HashSet::from_iter(ctx.parsed(get_all_walls).into_iter().take(ctx.param("take_first", TAKE_FIRST)));
+    render_maze(&maze, x_dim, y_dim);
 
-    println!("Day 18 task 1 result is {}", solve_maze(&maze).unwrap());
+    let result = solve_maze(&maze, x_dim, y_dim).unwrap();
+    println!("Day 18 task 1 result is {}", result);
+    ctx.answer(1, result);
 }
 
-pub fn main_day18_task2() {
+pub fn main_day18_task2(ctx: &Context) {
     _ = env_logger::try_init();
 
-    let walls = get_all_walls();
-    let mut maze: HashSet<Point> = HashSet::from_iter(walls.iter().take(TAKE_FIRST).cloned());
+    let x_dim = ctx.param("width", X_DIM);
+    let y_dim = ctx.param("height", Y_DIM);
+    let take_first = ctx.param("take_first", TAKE_FIRST);
+    let walls = ctx.parsed(get_all_walls);
+    let mut maze: HashSet<Point> = HashSet::from_iter(walls.iter().take(take_first).cloned());
     let mut next_wall = Point{x:0, y:0};
 
     for i in 0.. {
         log::info!("{}", i);
-        match solve_maze(&maze) {
+        if ctx.progress().is_cancelled() { return }
+        ctx.progress().report(i as u64, Some(walls.len().saturating_sub(take_first) as u64));
+        match solve_maze(&maze, x_dim, y_dim) {
             None => {
                 println!("Day 18 task 2 result is {},{}", next_wall.x, next_wall.y);
+                ctx.answer(2, format!("{},{}", next_wall.x, next_wall.y));
                 break
             },
             _ => (),
         }
-        next_wall = walls[TAKE_FIRST + i];
+        next_wall = walls[take_first + i];
         maze.insert(next_wall);
     }
 }
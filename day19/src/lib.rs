@@ -1,23 +1,22 @@
-use std::collections::HashMap;
-
 use env_logger;
 use log;
 
-use utils;
+use utils::Context;
+use utils::parse;
+use utils::memo::Memo;
 
-const FILENAME: &str = "day19\\data.txt";
+fn get_data(ctx: &Context) -> (Vec<String>, Vec<String>) {
+    let lines: Vec<String> = ctx.lines().collect();
+    let sections = parse::sections(&lines, &["towels", "designs"]).unwrap();
 
-fn get_data() -> (Vec<String>, Vec<String>) {
-    let towels = utils::file_to_iter(FILENAME)
-        .next()
-        .unwrap()
-        .split(", ")
-        .map(|s| s.to_string())
-        .collect::<Vec<String>>();
+    let towels = sections.get("towels");
+    towels.expect_lines(1).unwrap();
+    let towels = towels.header().list::<String>(",").unwrap();
 
-    let patterns = utils::file_to_iter(FILENAME)
-        .skip(2)
-        .map(|s| s.to_string())
+    let patterns = sections
+        .get("designs")
+        .fields()
+        .map(|s| s.trim().to_string())
         .collect::<Vec<String>>();
 
     (towels, patterns)
@@ -38,44 +37,51 @@ fn is_valid(pattern: &str, towels: &Vec<String>) -> bool {
     false
 }
 
-fn is_valid_with_count(pattern: &str, towels: &Vec<String>, counts: &mut HashMap<String, u64>) -> u64 {
-    if let Some(counter) = counts.get(pattern) { return *counter; }
-    
-    let mut counter = 0;
-    let t = towels
-        .iter()
-        .filter(|&s| pattern.contains(s))
-        .cloned()
-        .collect::<Vec<String>>();
-
-    for i in t.iter() {
-        if i == pattern {
-            counter += 1;
-            continue
-        }
-        if *i == pattern[0..i.len()] {
-            counter += is_valid_with_count(&pattern[i.len()..], &t, counts);
+fn is_valid_with_count(pattern: &str, towels: &[String], counts: &mut Memo<String, u64>) -> u64 {
+    counts.get_or_insert_with(pattern, |counts| {
+        let mut counter = 0;
+        let t = towels
+            .iter()
+            .filter(|&s| pattern.contains(s))
+            .cloned()
+            .collect::<Vec<String>>();
+
+        for i in t.iter() {
+            if i == pattern {
+                counter += 1;
+                continue
+            }
+            if *i == pattern[0..i.len()] {
+                counter += is_valid_with_count(&pattern[i.len()..], &t, counts);
+            }
         }
-    }
 
-    counts.insert(pattern.to_string(), counter);
-    counter
+        counter
+    })
+}
+
+pub fn parse_input(ctx: &Context) {
+    ctx.keep_parsed(get_data(ctx));
 }
 
-pub fn main_day19_task1() {
+pub fn main_day19_task1(ctx: &Context) {
     _ = env_logger::try_init();
 
-    let (towels, patterns) = get_data();
+    let (towels, patterns) = ctx.parsed(get_data);
 
-    println!("Day 19 task 1 result is {}", patterns.iter().filter(|p| is_valid(&p, &towels)).count());
+    let result = patterns.iter().filter(|p| is_valid(&p, &towels)).count();
+    println!("Day 19 task 1 result is {}", result);
+    ctx.answer(1, result);
 }
 
-pub fn main_day19_task2() {
+pub fn main_day19_task2(ctx: &Context) {
     _ = env_logger::try_init();
 
-    let (towels, patterns) = get_data();
-    let mut counts = HashMap::new();
+    let (towels, patterns) = ctx.parsed(get_data);
+    let mut counts = Memo::new();
 
-    println!("Day 19 task 2 result is {}", patterns.iter().map(|p| is_valid_with_count(&p, &towels, &mut counts)).sum::<u64>());
-    log::debug!("{}", counts.len());
+    let result = patterns.iter().map(|p| is_valid_with_count(&p, &towels, &mut counts)).sum::<u64>();
+    println!("Day 19 task 2 result is {}", result);
+    ctx.answer(2, result);
+    log::debug!("{}", counts.stats());
 }
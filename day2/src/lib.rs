@@ -1,4 +1,6 @@
-use utils;
+use std::{fmt, str::FromStr};
+
+use utils::Context;
 
 fn get_numbers(line: &str) -> Vec<i64> {
     line
@@ -7,57 +9,403 @@ fn get_numbers(line: &str) -> Vec<i64> {
         .collect()
 }
 
-fn is_block_safe(numbers: &[i64]) -> bool {
-    let mut is_increasing = true;
-    
-    match numbers.len() {
-        0 | 1 => return true,
-        _ => {},
+fn get_reports(ctx: &Context) -> Vec<Vec<i64>> {
+    ctx.lines().map(|line| get_numbers(&line)).collect()
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Direction {
+    Increasing,
+    Decreasing,
+    Either,
+}
+
+impl FromStr for Direction {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "increasing" => Ok(Direction::Increasing),
+            "decreasing" => Ok(Direction::Decreasing),
+            "either" => Ok(Direction::Either),
+            _ => Err(format!("unknown direction {s:?}, expected increasing, decreasing or either")),
+        }
+    }
+}
+
+/// The rule broken by a step between two levels
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Violation {
+    WrongDirection,
+    Flat,
+    StepTooSmall,
+    StepTooLarge,
+}
+
+impl fmt::Display for Violation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Violation::WrongDirection => "wrong direction",
+            Violation::Flat => "flat step",
+            Violation::StepTooSmall => "step too small",
+            Violation::StepTooLarge => "step too large",
+        };
+        write!(f, "{name}")
+    }
+}
+
+/// When a report counts as safe, the default are the rules of the puzzle
+/// Steps are measured in the direction of the report, a flat step is only allowed if `strict` is off
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RuleSet {
+    pub min_step: i64,
+    pub max_step: i64,
+    pub strict: bool,
+    pub direction: Direction,
+}
+
+impl Default for RuleSet {
+    fn default() -> Self {
+        RuleSet { min_step: 1, max_step: 3, strict: true, direction: Direction::Either }
+    }
+}
+
+impl RuleSet {
+    fn from_params(ctx: &Context) -> Self {
+        let default = RuleSet::default();
+        RuleSet {
+            min_step: ctx.param("min_step", default.min_step),
+            max_step: ctx.param("max_step", default.max_step),
+            strict: ctx.param("strict", default.strict),
+            direction: ctx.param("direction", default.direction),
+        }
+    }
+
+    // The directions a report may go in, as is_increasing
+    fn directions(&self) -> &'static [bool] {
+        match self.direction {
+            Direction::Increasing => &[true],
+            Direction::Decreasing => &[false],
+            Direction::Either => &[true, false],
+        }
+    }
+
+    fn check_step(&self, prev: i64, number: i64, is_increasing: bool) -> Option<Violation> {
+        let step = if is_increasing { number - prev } else { prev - number };
+        if step < 0 { return Some(Violation::WrongDirection) }
+        if step == 0 { return self.strict.then_some(Violation::Flat) }
+        if step < self.min_step { return Some(Violation::StepTooSmall) }
+        if step > self.max_step { return Some(Violation::StepTooLarge) }
+        None
+    }
+
+    /// The first level which breaks a rule when following the one before it, and the rule
+    /// Without a fixed direction the report goes the way of its first step which is not flat
+    pub fn first_violation(&self, numbers: &[i64]) -> Option<(usize, Violation)> {
+        let is_increasing = match self.direction {
+            Direction::Increasing => true,
+            Direction::Decreasing => false,
+            Direction::Either => numbers.windows(2).find(|w| w[0] != w[1]).is_none_or(|w| w[0] < w[1]),
+        };
+        numbers
+            .windows(2)
+            .enumerate()
+            .find_map(|(i, w)| self.check_step(w[0], w[1], is_increasing).map(|violation| (i + 1, violation)))
+    }
+
+    pub fn is_safe(&self, numbers: &[i64]) -> bool {
+        self.first_violation(numbers).is_none()
+    }
+}
+
+// The fewest levels to remove so that the rest is safe in the given direction, if that is at most `k`
+// Linear in the number of levels for a fixed `k`: a kept level can only follow one of the k + 1 levels before it
+fn dampen_in(numbers: &[i64], rules: &RuleSet, k: usize, is_increasing: bool) -> Option<Vec<usize>> {
+    let n = numbers.len();
+    if n == 0 { return Some(vec![]) }
+
+    // For level i kept: the fewest removals up to it and the kept level before it
+    let mut best: Vec<Option<(usize, Option<usize>)>> = Vec::with_capacity(n);
+    for i in 0..n {
+        let mut removals = (i <= k).then_some((i, None));
+        for prev in i.saturating_sub(k + 1)..i {
+            let Some((before, _)) = best[prev] else { continue };
+            let count = before + i - prev - 1;
+            if count > k || rules.check_step(numbers[prev], numbers[i], is_increasing).is_some() { continue }
+            if removals.is_none_or(|(r, _)| count < r) { removals = Some((count, Some(prev))); }
+        }
+        best.push(removals);
+    }
+
+    // The last kept level, everything after it is removed
+    let (mut last, _) = (n.saturating_sub(k + 1)..n)
+        .filter_map(|i| best[i].map(|(r, _)| (i, r + n - 1 - i)))
+        .filter(|(_, count)| *count <= k)
+        .min_by_key(|(_, count)| *count)?;
+
+    let mut kept = vec![false; n];
+    loop {
+        kept[last] = true;
+        match best[last].unwrap().1 {
+            Some(prev) => last = prev,
+            None => break,
+        }
+    }
+    Some((0..n).filter(|i| !kept[*i]).collect())
+}
+
+/// Problem Dampener tolerating up to `k` bad levels: the indices of the fewest levels to remove for a safe report,
+/// None if more than `k` would have to go
+pub fn dampen(numbers: &[i64], rules: &RuleSet, k: usize) -> Option<Vec<usize>> {
+    rules
+        .directions()
+        .iter()
+        .filter_map(|is_increasing| dampen_in(numbers, rules, k, *is_increasing))
+        .min_by_key(|removed| removed.len())
+}
+
+/// Why a report is unsafe and whether the dampener saves it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Diagnosis {
+    /// The first level breaking a rule, see `RuleSet::first_violation`, None for a safe report
+    pub violation: Option<(usize, Violation)>,
+    /// Levels the dampener removes, None if it can not make the report safe
+    /// Empty for a safe report
+    pub rescue: Option<Vec<usize>>,
+}
+
+impl Diagnosis {
+    pub fn is_safe(&self) -> bool {
+        self.violation.is_none()
     }
-    
-    let mut prev_number = numbers[0];
-    if numbers[1] < prev_number {
-        is_increasing = false;
+
+    pub fn is_safe_dampened(&self) -> bool {
+        self.rescue.is_some()
     }
-    for &number in numbers.iter().skip(1) {
-        if (number - prev_number).abs() > 3 { return false }
-        if is_increasing && number <= prev_number { return false }
-        if !is_increasing && number >= prev_number { return false }
-        prev_number = number;
+}
+
+pub fn diagnose(numbers: &[i64], rules: &RuleSet, k: usize) -> Diagnosis {
+    match rules.first_violation(numbers) {
+        None => Diagnosis { violation: None, rescue: Some(vec![]) },
+        violation => Diagnosis { violation, rescue: dampen(numbers, rules, k) },
     }
+}
 
-    true
+/// The diagnosis of every report, with the rules and the number of removals of the parameters
+pub fn diagnose_reports(ctx: &Context) -> Vec<Diagnosis> {
+    let rules = RuleSet::from_params(ctx);
+    let removals = ctx.param("removals", 1);
+    ctx.parsed(get_reports).iter().map(|numbers| diagnose(numbers, &rules, removals)).collect()
 }
 
-fn is_line_error_free(line: &str) -> bool {
-    let numbers = get_numbers(line);
-    is_block_safe(&numbers)
+/// A safe version of a report with the fewest levels changed
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Correction {
+    /// Indices of the changed levels
+    pub changed: Vec<usize>,
+    pub report: Vec<i64>,
 }
 
-fn is_line_with_max_one_error(line: &str) -> bool {
-    let numbers = get_numbers(line);
-    
-    //Check whole sequence
-    if is_block_safe(&numbers) { return true }
-    
-    //Check all possible slices leaving 1 element out
-    for i in 0..numbers.len() {
-        let mut leave_one = numbers.iter().take(i).collect::<Vec<_>>();
-        leave_one.extend(numbers.iter().skip(i+1));
-        if is_block_safe(&leave_one.into_iter().cloned().collect::<Vec<_>>()) { return true }
+impl RuleSet {
+    // The smallest and largest step which is not flat, None if there is none
+    fn step_range(&self) -> Option<(i64, i64)> {
+        let low = self.min_step.max(1);
+        (low <= self.max_step).then_some((low, self.max_step))
     }
 
-    false
+    // Whether `steps` steps can climb exactly `rise`
+    fn can_climb(&self, rise: i64, steps: i64) -> bool {
+        if rise == 0 && !self.strict { return true }
+        let Some((low, high)) = self.step_range() else { return false };
+        if self.strict { return steps * low <= rise && rise <= steps * high }
+        // Some of the steps are flat, the fewest other steps needed have to fit
+        rise > 0 && (rise + high - 1) / high <= steps.min(rise / low)
+    }
+
+    // The steps climbing `rise` in `steps` steps, can_climb has to hold
+    fn climb(&self, rise: i64, steps: i64) -> Vec<i64> {
+        let (low, high) = self.step_range().unwrap_or((0, 0));
+        let moving = if self.strict { steps } else { (rise + high - 1).checked_div(high).unwrap_or(0) };
+        let mut rest = rise - moving * low;
+        (0..steps)
+            .map(|i| {
+                if i >= moving { return 0 }
+                let extra = rest.min(high - low);
+                rest -= extra;
+                low + extra
+            })
+            .collect()
+    }
+}
+
+// The fewest changes for a safe report going up
+fn correct_increasing(numbers: &[i64], rules: &RuleSet) -> Option<Correction> {
+    let n = numbers.len();
+    // Levels before the first and after the last unchanged one can always be made up
+    let free_step = match rules.step_range() {
+        Some((low, _)) => low,
+        None if !rules.strict || n < 2 => 0,
+        None => return None,
+    };
+    if n == 0 { return Some(Correction { changed: vec![], report: vec![] }) }
+
+    // For level i unchanged: the fewest changes up to it and the unchanged level before it
+    let mut best: Vec<(usize, Option<usize>)> = Vec::with_capacity(n);
+    for i in 0..n {
+        let mut changes = (i, None);
+        for prev in 0..i {
+            let count = best[prev].0 + i - prev - 1;
+            if count < changes.0 && rules.can_climb(numbers[i] - numbers[prev], (i - prev) as i64) {
+                changes = (count, Some(prev));
+            }
+        }
+        best.push(changes);
+    }
+    let last = (0..n).min_by_key(|i| best[*i].0 + n - 1 - i).unwrap();
+
+    let mut unchanged = vec![last];
+    while let Some(prev) = best[*unchanged.last().unwrap()].1 { unchanged.push(prev); }
+    unchanged.reverse();
+
+    let mut report = numbers.to_vec();
+    for i in (0..unchanged[0]).rev() { report[i] = report[i + 1] - free_step; }
+    for pair in unchanged.windows(2) {
+        let (from, to) = (pair[0], pair[1]);
+        for (i, step) in (from + 1..to).zip(rules.climb(numbers[to] - numbers[from], (to - from) as i64)) {
+            report[i] = report[i - 1] + step;
+        }
+    }
+    for i in last + 1..n { report[i] = report[i - 1] + free_step; }
+
+    let changed = (0..n).filter(|i| report[*i] != numbers[*i]).collect();
+    Some(Correction { changed, report })
+}
+
+/// The fewest levels to change to any value for a safe report, with one such report
+/// None if no report of this length is safe under the rules
+pub fn correct(numbers: &[i64], rules: &RuleSet) -> Option<Correction> {
+    let negated = numbers.iter().map(|n| -n).collect::<Vec<i64>>();
+    rules
+        .directions()
+        .iter()
+        .filter_map(|is_increasing| match is_increasing {
+            true => correct_increasing(numbers, rules),
+            false => correct_increasing(&negated, rules).map(|c| Correction { report: c.report.iter().map(|n| -n).collect(), ..c }),
+        })
+        .min_by_key(|c| c.changed.len())
+}
+
+/// The correction of every report, with the rules of the parameters
+pub fn correct_reports(ctx: &Context) -> Vec<Option<Correction>> {
+    let rules = RuleSet::from_params(ctx);
+    ctx.parsed(get_reports).iter().map(|numbers| correct(numbers, &rules)).collect()
+}
+
+pub fn parse_input(ctx: &Context) {
+    ctx.keep_parsed(get_reports(ctx));
 }
 
-pub fn main_day2_task1() {
-    let lines = utils::file_to_string_vector("day2\\data.txt");
-    let result = lines.iter().filter(|line| is_line_error_free(line)).count();
+pub fn main_day2_task1(ctx: &Context) {
+    let result = diagnose_reports(ctx).iter().filter(|d| d.is_safe()).count();
     println!("Day 2 task 1 result is {}", result);
+    ctx.answer(1, result);
 }
 
-pub fn main_day2_task2() {
-    let lines = utils::file_to_string_vector("day2\\data.txt");
-    let result = lines.iter().filter(|line| is_line_with_max_one_error(line)).count();
+pub fn main_day2_task2(ctx: &Context) {
+    let result = diagnose_reports(ctx).iter().filter(|d| d.is_safe_dampened()).count();
     println!("Day 2 task 2 result is {}", result);
+    ctx.answer(2, result);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use utils::random::Lcg;
+
+    const SAMPLE: [&str; 6] = ["7 6 4 2 1", "1 2 7 8 9", "9 7 6 2 1", "1 3 2 4 5", "8 6 4 4 1", "1 3 6 7 9"];
+
+    #[test]
+    fn test_dampen() {
+        let dampened = SAMPLE.map(|line| dampen(&get_numbers(line), &RuleSet::default(), 1));
+        assert_eq!(dampened, [Some(vec![]), None, None, Some(vec![2]), Some(vec![3]), Some(vec![])]);
+        assert_eq!(dampen(&get_numbers("1 2 7 8 9"), &RuleSet::default(), 2), Some(vec![0, 1]));
+        assert_eq!(dampen(&[], &RuleSet::default(), 0), Some(vec![]));
+
+        // Against trying every set of removals
+        let mut rng = Lcg::new(3);
+        for _ in 0..500 {
+            let numbers = (0..7).map(|_| rng.below(8) as i64).collect::<Vec<i64>>();
+            let fewest = (0..1u32 << numbers.len())
+                .filter(|mask| {
+                    let kept = (0..numbers.len()).filter(|i| mask & (1 << i) == 0).map(|i| numbers[i]).collect::<Vec<i64>>();
+                    RuleSet::default().is_safe(&kept)
+                })
+                .map(|mask| mask.count_ones() as usize)
+                .min()
+                .unwrap();
+            for k in 0..4 {
+                let removed = dampen(&numbers, &RuleSet::default(), k);
+                assert_eq!(removed.as_ref().map(|r| r.len()), (fewest <= k).then_some(fewest), "{numbers:?}");
+                if let Some(removed) = removed {
+                    let kept = (0..numbers.len()).filter(|i| !removed.contains(i)).map(|i| numbers[i]).collect::<Vec<i64>>();
+                    assert!(RuleSet::default().is_safe(&kept));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_diagnose() {
+        let rules = RuleSet::default();
+        let diagnoses = SAMPLE.map(|line| diagnose(&get_numbers(line), &rules, 1));
+        assert!(diagnoses[0].is_safe());
+        assert_eq!(diagnoses[1].violation, Some((2, Violation::StepTooLarge)));
+        assert_eq!(diagnoses[3], Diagnosis { violation: Some((2, Violation::WrongDirection)), rescue: Some(vec![2]) });
+        assert_eq!(diagnoses[4].violation, Some((3, Violation::Flat)));
+        assert_eq!(diagnoses.iter().filter(|d| d.is_safe_dampened()).count(), 4);
+
+        let rules = RuleSet { min_step: 2, max_step: 4, strict: false, direction: Direction::Decreasing };
+        assert_eq!(rules.first_violation(&get_numbers("8 6 6 2")), None);
+        assert_eq!(rules.first_violation(&get_numbers("8 7 5")), Some((1, Violation::StepTooSmall)));
+        assert_eq!(rules.first_violation(&get_numbers("1 3 6 7 9")), Some((1, Violation::WrongDirection)));
+        assert_eq!(dampen(&get_numbers("8 7 5 1"), &rules, 1), Some(vec![1]));
+    }
+
+    #[test]
+    fn test_correct() {
+        let rules = RuleSet::default();
+        let corrections = SAMPLE.map(|line| correct(&get_numbers(line), &rules).unwrap());
+        assert_eq!(corrections.iter().map(|c| c.changed.len()).collect::<Vec<usize>>(), vec![0, 1, 1, 2, 1, 0]);
+        assert_eq!(corrections[1].report, vec![1, 2, 5, 8, 9]);
+
+        let rules = RuleSet { min_step: 4, max_step: 2, strict: true, direction: Direction::Either };
+        assert_eq!(correct(&[1, 2], &rules), None);
+        assert_eq!(correct(&[1], &rules).unwrap().changed, vec![]);
+
+        // Against trying every change within a range of values
+        let mut rng = Lcg::new(11);
+        for strict in [true, false] {
+            let rules = RuleSet { min_step: 2, max_step: 3, strict, direction: Direction::Either };
+            for _ in 0..100 {
+                let numbers = (0..4).map(|_| rng.below(10) as i64).collect::<Vec<i64>>();
+                let fewest = (0..1u32 << numbers.len())
+                    .filter(|mask| {
+                        let changed = (0..numbers.len()).filter(|i| mask & (1 << i) != 0).collect::<Vec<usize>>();
+                        (0..21i64.pow(changed.len() as u32)).any(|values| {
+                            let mut report = numbers.clone();
+                            for (k, i) in changed.iter().enumerate() { report[*i] = (values / 21i64.pow(k as u32)) % 21 - 5; }
+                            rules.is_safe(&report)
+                        })
+                    })
+                    .map(|mask| mask.count_ones() as usize)
+                    .min()
+                    .unwrap();
+
+                let correction = correct(&numbers, &rules).unwrap();
+                assert_eq!(correction.changed.len(), fewest, "{numbers:?}");
+                assert!(rules.is_safe(&correction.report), "{numbers:?} {correction:?}");
+                assert!((0..numbers.len()).all(|i| (correction.report[i] != numbers[i]) == correction.changed.contains(&i)));
+            }
+        }
+    }
 }
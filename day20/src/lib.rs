@@ -4,9 +4,7 @@ use env_logger;
 use log;
 use itertools::Itertools;
 
-use utils;
-
-const FILENAME: &str = "day20\\data.txt";
+use utils::{self, Context};
 
 #[derive(Debug, Eq, PartialEq, Hash, Copy, Clone)]
 struct Point {
@@ -21,14 +19,14 @@ impl Point {
     }
 }
 
-fn get_maze() -> (HashSet<Point>, Point, Point, i32, i32) {
+fn get_maze(ctx: &Context) -> (HashSet<Point>, Point, Point, i32, i32) {
     let mut start: Point = Point{x:0, y:0};
     let mut end: Point = Point{x:0, y:0};
     let mut walls = HashSet::new();
-    let y_dim = utils::file_to_string_vector(FILENAME).len() as i32;
-    let x_dim = utils::file_to_string_vector(FILENAME)[0].len() as i32;
+    let y_dim = utils::file_to_string_vector(ctx.input()).len() as i32;
+    let x_dim = utils::file_to_string_vector(ctx.input())[0].len() as i32;
 
-    for (i, line) in utils::file_to_iter(FILENAME).enumerate() {
+    for (i, line) in ctx.lines().enumerate() {
         for (j, c) in line.chars().enumerate() {
             match c {
                 'S' => { start = Point { x: j as i32, y: i as i32 }; },
@@ -148,10 +146,14 @@ fn get_cheats(walls: &HashSet<Point>, solution: &HashMap<Point, i32>, x_dim: i32
     cheats
 }
 
-pub fn main_day20_task1() {
+pub fn parse_input(ctx: &Context) {
+    ctx.keep_parsed(get_maze(ctx));
+}
+
+pub fn main_day20_task1(ctx: &Context) {
     _ = env_logger::try_init();
 
-    let (walls, start, end, x_dim, y_dim) = get_maze();
+    let (walls, start, end, x_dim, y_dim) = ctx.parsed(get_maze);
     render_maze(&walls, x_dim, y_dim, &start, &end);
 
     let solution = get_solution(&start, &end, &walls);
@@ -162,27 +164,29 @@ pub fn main_day20_task1() {
         log::debug!("{} {}", cheat, cheats[cheat]);
     }
 
-    println!("Day 20 task 1 result is {}", cheats
+    let min_cheat_amount = ctx.param("min_cheat_amount", 100);
+    let result = cheats
         .iter()
         .map(|cheat| {
-            match *cheat.0 >= 100 {
+            match *cheat.0 >= min_cheat_amount {
                 true => *cheat.1,
                 false => 0,
             }
         })
-        .sum::<i32>()
-    );
+        .sum::<i32>();
+    println!("Day 20 task 1 result is {}", result);
+    ctx.answer(1, result);
 }
 
-pub fn main_day20_task2() {
+pub fn main_day20_task2(ctx: &Context) {
     _ = env_logger::builder().format_timestamp(None).try_init();
 
-    let (walls, start, end, x_dim, y_dim) = get_maze();
+    let (walls, start, end, x_dim, y_dim) = ctx.parsed(get_maze);
     let solution = get_solution(&start, &end, &walls);
     log::info!("Solution length = {}, fastest time = {}", solution.len(), solution.len() - 1);
 
-    let limit = 20;
-    let min_cheat_amount = 100;
+    let limit = ctx.param("limit", 20);
+    let min_cheat_amount = ctx.param("min_cheat_amount", 100);
 
     let mut cheats = HashMap::new();
     for (p, pos) in solution.iter() {
@@ -204,7 +208,7 @@ pub fn main_day20_task2() {
             }
         }
     }
-    println!("Day 20 task 2 result is {}", cheats
+    let result = cheats
         .iter()
         .map(|cheat| {
             match *cheat.0 >= min_cheat_amount {
@@ -212,6 +216,7 @@ pub fn main_day20_task2() {
                 false => 0,
             }
         })
-        .sum::<i32>()
-    );
+        .sum::<i32>();
+    println!("Day 20 task 2 result is {}", result);
+    ctx.answer(2, result);
 }
@@ -1,9 +1,8 @@
-use std::collections::HashMap;
-
 use env_logger;
 use log;
 
-//use utils;
+use utils::Context;
+use utils::memo::{Interner, Memo};
 
 //const FILENAME: &str = "day21\\data_sample.txt";
 
@@ -63,7 +62,15 @@ fn solve(s: &str) -> String {
     s2
 }
 
-pub fn main_day21_task1() {
+// The codes are hardcoded in the tasks, the input is only checked to be door codes like 805A
+pub fn parse_input(ctx: &Context) {
+    for line in ctx.lines() {
+        let digits = line.strip_suffix('A').unwrap();
+        assert!(digits.len() == 3 && digits.chars().all(|c| c.is_ascii_digit()), "Not a door code: {line}");
+    }
+}
+
+pub fn main_day21_task1(ctx: &Context) {
     _ = env_logger::builder().format_timestamp(None).try_init();
     
     let _v = vec![
@@ -97,12 +104,20 @@ pub fn main_day21_task1() {
         ("<^^A<A>vvA>A", 540), // 540A
     ];
 
+    // The complexity of a code comes from the shortest of its candidates
     let v_all = vec![v1, v2, v3, v4, v5];
+    let mut result = 0;
     for v in v_all {
+        let mut shortest = usize::MAX;
         for (s, nr) in v.iter() {
-            log::debug!("{} {nr}", solve(&solve(s)).len());
+            let length = solve(&solve(s)).len();
+            log::debug!("{} {nr}", length);
+            shortest = shortest.min(length);
         }
+        result += shortest * v[0].1;
     }
+    println!("Day 21 task 1 result is {}", result);
+    ctx.answer(1, result);
 
     /*
     let v2 = vec![
@@ -134,39 +149,30 @@ pub fn main_day21_task1() {
     */
 }
 
-fn get_or_solve(s: String, iter_nr: u32, memory: &mut HashMap<String, HashMap<u32, usize>>) -> usize {
-    if let Some(v1) = memory.get(&s) {
-        if let Some(v2) = v1.get(&iter_nr) {
-            return *v2;
-        }
-    }
-    
-    let mut total = 0;
-    if iter_nr > 0 {
-        let s_next = solve(&s);
+// The memory is keyed by the interned pattern and the iterations
+fn get_or_solve(s: &str, iter_nr: u32, memory: &mut Memo<(u32, u32), usize>, patterns: &mut Interner) -> usize {
+    let key = (patterns.intern(s), iter_nr);
+    memory.get_or_insert_with(&key, |memory| {
+        if iter_nr == 0 { return s.len() }
+
+        let mut total = 0;
+        let s_next = solve(s);
         let parts = s_next.split("AA").collect::<Vec<&str>>();
         // The last one does not have "AA"
         for i in 0..parts.len() - 1 {
-            let v = get_or_solve(parts[i].to_string() + "AA", iter_nr - 1, memory);
-            total += v;
+            total += get_or_solve(&(parts[i].to_string() + "AA"), iter_nr - 1, memory, patterns);
         }
-        let v = get_or_solve(parts[parts.len() - 1].to_string(), iter_nr - 1, memory);
-        total += v;
-    }
-    else {
-        total = s.len();
-    }
-
-    memory.entry(s).or_insert(HashMap::new()).insert(iter_nr, total);
+        total += get_or_solve(parts[parts.len() - 1], iter_nr - 1, memory, patterns);
 
-    total
+        total
+    })
 }
 
-pub fn main_day21_task2() {
+pub fn main_day21_task2(ctx: &Context) {
     _ = env_logger::builder().format_timestamp(None).try_init();
 
-    // 1. pattern, 2. iterations
-    let mut memory: HashMap<String, HashMap<u32, usize>> = HashMap::new();
+    let mut memory = Memo::new();
+    let mut patterns = Interner::new();
     
     let v = vec![
         ("<^^^AvvvA^^Avv>A", 805), // 805A -> 72
@@ -176,12 +182,14 @@ pub fn main_day21_task2() {
         ("<^^A<A>vvA>A", 540), // 540A -> 68
     ];
 
-    let iter_nr = 25;
+    let iter_nr = ctx.param("depth", 25);
     let mut result = 0;
     for (s, nr) in v.iter() {
-        get_or_solve(s.to_string(), iter_nr, &mut memory);
-        result += *nr * memory.get(*s).unwrap().get(&iter_nr).unwrap();
-        log::debug!("{} {}", nr, memory.get(*s).unwrap().get(&iter_nr).unwrap());
+        let length = get_or_solve(s, iter_nr, &mut memory, &mut patterns);
+        result += *nr * length;
+        log::debug!("{} {}", nr, length);
     }
+    log::debug!("{}", memory.stats());
     println!("result={}", result);
+    ctx.answer(2, result);
 }
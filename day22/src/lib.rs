@@ -1,11 +1,12 @@
 use std::collections::{HashMap, HashSet};
+use std::path::Path;
 
 use env_logger;
 use log;
 
-use utils;
+use utils::{self, Context};
 
-fn get_data(filename: &str) -> Vec<i64> {
+fn get_data(filename: impl AsRef<Path>) -> Vec<i64> {
     utils::file_to_iter(filename)
         .map(|line| line.parse::<i64>().unwrap())
         .collect()
@@ -53,34 +54,44 @@ fn get_sequence_map(nr: i64, iterations: usize, all_keys: &mut HashSet<(i64, i64
     smap
 }
 
-pub fn main_day22_task1() {
+pub fn parse_input(ctx: &Context) {
+    ctx.keep_parsed(get_data(ctx.input()));
+}
+
+pub fn main_day22_task1(ctx: &Context) {
     _ = env_logger::builder().format_timestamp(None).try_init();
     
-    let nrs = get_data("day22\\data.txt");
+    let nrs = ctx.parsed(|ctx| get_data(ctx.input()));
+    let iterations = ctx.param("iterations", 2000);
     let mut total = 0;
     for nr in nrs.iter() {
         let mut nr2 = *nr;
-        for _ in 0..2000 {
+        for _ in 0..iterations {
             nr2 = get_secret_number(nr2);
         }
         total += nr2;
     }
 
     println!("Day 22 task 1 result is {}", total);
+    ctx.answer(1, total);
 }
 
-pub fn main_day22_task2() {
+pub fn main_day22_task2(ctx: &Context) {
     _ = env_logger::builder().format_timestamp(None).try_init();
     
-    let nrs = get_data("day22\\data.txt");
+    let nrs = ctx.parsed(|ctx| get_data(ctx.input()));
+    let iterations = ctx.param("iterations", 2000);
     let mut all_keys = HashSet::new();
     let mut all_smaps = vec![];
     for nr in nrs.iter() {
-        all_smaps.push(get_sequence_map(*nr, 2000, &mut all_keys));
+        all_smaps.push(get_sequence_map(*nr, iterations, &mut all_keys));
     }
     log::debug!("{}", all_smaps.len());
     let mut max_result: i64 = 0;
-    for k in all_keys.iter() {
+    for (i, k) in all_keys.iter().enumerate() {
+        if ctx.progress().is_cancelled() { return }
+        ctx.progress().report(i as u64, Some(all_keys.len() as u64));
+
         let mut result = 0;
         for smap in all_smaps.iter() {
             if let Some(v) = smap.get(k) {
@@ -93,6 +104,7 @@ pub fn main_day22_task2() {
         }
     }
     println!("Day 22 task 2 result is {}", max_result);
+    ctx.answer(2, max_result);
 }
 
 #[cfg(test)]
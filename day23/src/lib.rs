@@ -1,26 +1,32 @@
 use std::collections::{HashMap, HashSet};
+use std::path::Path;
 
 use env_logger;
 use log;
 use lazy_static::lazy_static;
-use regex::Regex;
 use itertools::Itertools;
 use queues::*;
 
-use utils;
+use utils::explore::{self, Explore};
+use utils::Context;
+use utils::parse::{self, Template};
 
 lazy_static!{
-    pub static ref COMPS: Regex = Regex::new(r"(?<comp1>[a-z]{2})-(?<comp2>[a-z]{2})").unwrap();
+    pub static ref COMPS: Template = Template::new("{word}-{word}");
 }
 
-fn get_data(filename: &str) -> (HashMap<String, HashSet<String>>, HashSet<String>) {
+fn get_connections(filename: &Path) -> Vec<(String, String)> {
+    parse::lines(utils::file_to_iter(filename), |line| {
+        let caps = COMPS.captures(line)?;
+        Ok((caps[0].to_string(), caps[1].to_string()))
+    }).unwrap()
+}
+
+fn get_data(connections: Vec<(String, String)>) -> (HashMap<String, HashSet<String>>, HashSet<String>) {
     let mut computers: HashMap<String, HashSet<String>> = HashMap::new();
     let mut candidates: HashSet<String> = HashSet::new();
     
-    for line in utils::file_to_iter(filename) {
-        let caps = COMPS.captures(&line).unwrap();
-        let mut comp1 = caps["comp1"].to_string();
-        let mut comp2 = caps["comp2"].to_string();
+    for (mut comp1, mut comp2) in connections {
         if comp1 > comp2 { (comp1, comp2) = (comp2, comp1); }
         if comp1.starts_with("t") {
             candidates.insert(comp1.clone());
@@ -38,32 +44,76 @@ fn get_data(filename: &str) -> (HashMap<String, HashSet<String>>, HashSet<String
     (computers, candidates)
 }
 
-fn get_data2(filename: &str) -> (HashMap<String, HashSet<String>>, HashSet<String>) {
-    let mut connections: HashMap<String, HashSet<String>> = HashMap::new();
+fn get_data2(connections: Vec<(String, String)>) -> (HashMap<String, HashSet<String>>, HashSet<String>) {
+    let mut by_computer: HashMap<String, HashSet<String>> = HashMap::new();
     let mut computers: HashSet<String> = HashSet::new();
     
-    for line in utils::file_to_iter(filename) {
-        let caps = COMPS.captures(&line).unwrap();
-        let comp1 = caps["comp1"].to_string();
-        let comp2 = caps["comp2"].to_string();
+    for (comp1, comp2) in connections {
         computers.insert(comp1.clone());
         computers.insert(comp2.clone());
         // Add both for faster lookup
-        connections.entry(comp1.clone()).or_insert(HashSet::new()).insert(comp2.clone());
-        connections.entry(comp2).or_insert(HashSet::new()).insert(comp1.clone());
+        by_computer.entry(comp1.clone()).or_insert(HashSet::new()).insert(comp2.clone());
+        by_computer.entry(comp2).or_insert(HashSet::new()).insert(comp1.clone());
     }
 
-    (connections, computers)
+    (by_computer, computers)
 }
 
 fn set_to_string(s: &HashSet<String>) -> String {
     s.iter().sorted().join(",")
 }
 
-pub fn main_day23_task1() {
+// Querying the network in the REPL
+struct Explorer {
+    connections: HashMap<String, HashSet<String>>,
+}
+
+impl Explorer {
+    fn neighbours(&self, computer: &str) -> Result<&HashSet<String>, String> {
+        self.connections.get(computer).ok_or(format!("Unknown computer: {computer}"))
+    }
+}
+
+impl Explore for Explorer {
+    fn commands(&self) -> &'static [(&'static str, &'static str)] {
+        &[
+            ("neighbours <computer>", "the computers connected to it"),
+            ("linked <computer> <computer>", "whether the two are connected"),
+            ("common <computer> <computer>", "the computers connected to both"),
+        ]
+    }
+
+    fn run(&mut self, command: &str, args: &[&str]) -> Result<String, String> {
+        match command {
+            "neighbours" => {
+                let computer: String = explore::arg(args, 0, "neighbours <computer>")?;
+                Ok(set_to_string(self.neighbours(&computer)?))
+            },
+            "linked" | "common" => {
+                let usage = format!("{command} <computer> <computer>");
+                let a = self.neighbours(&explore::arg::<String>(args, 0, &usage)?)?;
+                let b: String = explore::arg(args, 1, &usage)?;
+                if command == "linked" { return Ok(a.contains(&b).to_string()) }
+                Ok(set_to_string(&a.intersection(self.neighbours(&b)?).cloned().collect()))
+            },
+            _ => Err(format!("Unknown command: {command}")),
+        }
+    }
+}
+
+pub fn explore(ctx: &Context) -> Box<dyn Explore> {
+    let (connections, _) = get_data2(get_connections(ctx.input()));
+    Box::new(Explorer { connections })
+}
+
+pub fn parse_input(ctx: &Context) {
+    ctx.keep_parsed(get_connections(ctx.input()));
+}
+
+pub fn main_day23_task1(ctx: &Context) {
     _ = env_logger::builder().format_timestamp(None).try_init();
 
-    let (computers, candidates) = get_data("day23\\data.txt");
+    let (computers, candidates) = get_data(ctx.parsed(|ctx| get_connections(ctx.input())));
     log::debug!("{:?}", computers);
     log::debug!("{:?}", candidates);
 
@@ -84,12 +134,13 @@ pub fn main_day23_task1() {
         }
     }
     println!("Day 23 task 1 result is {}", result);
+    ctx.answer(1, result);
 }
 
-pub fn main_day23_task2() {
+pub fn main_day23_task2(ctx: &Context) {
     _ = env_logger::builder().format_timestamp(None).try_init();
 
-    let (connections, computers) = get_data2("day23\\data.txt");
+    let (connections, computers) = get_data2(ctx.parsed(|ctx| get_connections(ctx.input())));
     log::debug!("{:?}", computers);
 
     // Current lan, possible extensions
@@ -103,6 +154,9 @@ pub fn main_day23_task2() {
     let mut max_set: String = String::from("");
     let mut seen = HashSet::new();
     loop {
+        if ctx.progress().is_cancelled() { return }
+        ctx.progress().report(seen.len() as u64, None);
+
         if let Ok((current_lan, extensions)) = q.remove() {
             for comp in extensions.iter() {
                 let new_extensions: HashSet<String> = extensions.intersection(connections.get(comp).unwrap()).cloned().collect();
@@ -130,4 +184,5 @@ pub fn main_day23_task2() {
     }
 
     println!("Day 23 task 2 result is {} with length of {}", max_set, max_length);
+    ctx.answer(2, max_set);
 }
@@ -1,16 +1,19 @@
 use std::collections::HashMap;
+use std::path::Path;
 
 use env_logger;
 use log;
 use lazy_static::lazy_static;
-use regex::Regex;
 use itertools::Itertools;
 
-use utils;
+use utils::checked::{self, Origin};
+use utils::explore::{self, Explore};
+use utils::Context;
+use utils::parse::{self, Template};
 
 lazy_static!{
-    pub static ref VAR: Regex = Regex::new(r"(?<var>[a-z0-9]{3}): (?<value>[0,1]{1})").unwrap();
-    pub static ref EXPR: Regex = Regex::new(r"(?<var1>[a-z0-9]{3}) (?<op>(XOR|OR|AND)) (?<var2>[a-z0-9]{3}) -> (?<var3>[a-z0-9]{3})").unwrap();
+    pub static ref VAR: Template = Template::new("{word}: {uint}");
+    pub static ref EXPR: Template = Template::new("{word} {word} {word} -> {word}");
 }
 
 #[derive(Debug, PartialEq)]
@@ -37,6 +40,7 @@ struct Expr {
     var2: String,
     var3: String,
     op: Op,
+    line: usize,
 }
 
 impl Expr {
@@ -65,38 +69,51 @@ impl Expr {
     }
 }
 
-fn get_data(filename: &str) -> (HashMap<String, u8>, HashMap<String, Expr>) {
+fn get_data(filename: impl AsRef<Path>) -> (HashMap<String, u8>, HashMap<String, Expr>) {
     let mut vars = HashMap::new();
     let mut exprs = HashMap::new();
 
-    for line in utils::file_to_iter(filename) {
-        if let Some(caps) = VAR.captures(&line) {
-            vars.insert(caps["var"].to_string(), caps["value"].parse::<u8>().unwrap());
-        }
-        else if let Some(caps) = EXPR.captures(&line) {
+    parse::lines(utils::file_to_iter(filename), |line| {
+        if line.contains("->") {
+            let caps = EXPR.captures(line)?;
+            if !["AND", "OR", "XOR"].contains(&caps[1].as_str()) {
+                return Err(caps[1].error(format!("unknown gate {:?}", caps[1].as_str())))
+            }
             exprs.insert(
-                caps["var3"].to_string(),
+                caps[3].to_string(),
                 Expr {
-                    var1: caps["var1"].to_string(),
-                    var2: caps["var2"].to_string(),
-                    var3: caps["var3"].to_string(),
-                    op: Op::from_string(&caps["op"]),
+                    var1: caps[0].to_string(),
+                    var2: caps[2].to_string(),
+                    var3: caps[3].to_string(),
+                    op: Op::from_string(&caps[1]),
+                    line: line.line(),
                 }
             );
         }
-    }
+        else if !line.trim().is_empty() {
+            let caps = VAR.captures(line)?;
+            vars.insert(caps[0].to_string(), caps[1].parse::<u8>()?);
+        }
+        Ok(())
+    }).unwrap();
 
     (vars, exprs)
 }
 
-fn solve1(filename: &str) -> isize {
-    let (mut vars, exprs) = get_data(filename);
+#[cfg(test)]
+fn solve1(filename: impl AsRef<Path>) -> isize {
+    let (vars, exprs) = get_data(filename);
+    output(vars, &exprs)
+}
+
+// The number on the z wires
+fn output(mut vars: HashMap<String, u8>, exprs: &HashMap<String, Expr>) -> isize {
     log::debug!("{:?}", vars);
     log::debug!("{:?}", exprs);
 
     for (var3, expr) in exprs.iter() {
         log::debug!("{} {:?}", var3, expr);
-        expr.solve(&mut vars, &exprs);
+        expr.solve(&mut vars, exprs);
     }
     log::debug!("{:?}", vars);
 
@@ -107,80 +124,117 @@ fn solve1(filename: &str) -> isize {
         .collect();
     log::debug!("{:?}", zs);
 
-    let s: Vec<u8> = zs.iter().sorted().rev().map(|(_, v)| *v).collect();
+    let s: Vec<(&String, &u8)> = zs.iter().sorted().rev().collect();
     log::debug!("s={:?}", s);
-    
-    isize::from_str_radix(&itertools::join(s, ""), 2).unwrap()
+
+    // Most significant bit first, an overflow is reported at the gate of the bit
+    s.iter().fold(0, |value: isize, (k, v)| {
+        let origin = Origin::new(24, exprs.get(*k).map_or(0, |expr| expr.line));
+        checked::add(checked::mul(value, 2, origin), **v as isize, origin)
+    })
+}
+
+// Evaluating wires in the REPL
+struct Explorer {
+    vars: HashMap<String, u8>,
+    exprs: HashMap<String, Expr>,
+}
+
+impl Explore for Explorer {
+    fn commands(&self) -> &'static [(&'static str, &'static str)] {
+        &[
+            ("wire <name>", "value of a wire"),
+            ("gate <name>", "the gate driving a wire"),
+            ("output", "the number on the z wires"),
+            ("input <name> <0|1>", "change the value of an input wire"),
+        ]
+    }
+
+    fn run(&mut self, command: &str, args: &[&str]) -> Result<String, String> {
+        match command {
+            "wire" => {
+                let name: String = explore::arg(args, 0, "wire <name>")?;
+                if let Some(v) = self.vars.get(&name) { return Ok(v.to_string()) }
+                let expr = self.exprs.get(&name).ok_or(format!("Unknown wire: {name}"))?;
+                Ok(expr.solve(&mut self.vars.clone(), &self.exprs).to_string())
+            },
+            "gate" => {
+                let name: String = explore::arg(args, 0, "gate <name>")?;
+                if self.vars.contains_key(&name) { return Ok(format!("{name} is an input wire")) }
+                let expr = self.exprs.get(&name).ok_or(format!("Unknown wire: {name}"))?;
+                Ok(format!("{} {:?} {} -> {} (line {})", expr.var1, expr.op, expr.var2, expr.var3, expr.line))
+            },
+            "output" => Ok(output(self.vars.clone(), &self.exprs).to_string()),
+            "input" => {
+                let name: String = explore::arg(args, 0, "input <name> <0|1>")?;
+                let value: u8 = explore::arg(args, 1, "input <name> <0|1>")?;
+                if value > 1 { return Err(format!("Invalid value: {value}")) }
+                let v = self.vars.get_mut(&name).ok_or(format!("Unknown input wire: {name}"))?;
+                *v = value;
+                Ok(format!("{name}: {value}"))
+            },
+            _ => Err(format!("Unknown command: {command}")),
+        }
+    }
 }
 
-pub fn main_day24_task1() {
+pub fn explore(ctx: &Context) -> Box<dyn Explore> {
+    let (vars, exprs) = get_data(ctx.input());
+    Box::new(Explorer { vars, exprs })
+}
+
+pub fn parse_input(ctx: &Context) {
+    ctx.keep_parsed(get_data(ctx.input()));
+}
+
+pub fn main_day24_task1(ctx: &Context) {
     _ = env_logger::builder().format_timestamp(None).try_init();
 
-    let sum = solve1("day24\\data.txt");
+    let (vars, exprs) = ctx.parsed(|ctx| get_data(ctx.input()));
+    let sum = output(vars, &exprs);
 
     println!("Day 24 task 1 result is {}", sum);
+    ctx.answer(1, sum);
 }
 
-pub fn main_day24_task2() {
+pub fn main_day24_task2(ctx: &Context) {
     // Based on https://www.reddit.com/r/adventofcode/comments/1hla5ql/2024_day_24_part_2_a_guide_on_the_idea_behind_the/
 
     _ = env_logger::builder().format_timestamp(None).try_init();
 
-    let (_vars, exprs) = get_data("day24\\data.txt");
+    let (_vars, exprs) = ctx.parsed(|ctx| get_data(ctx.input()));
 
-    let mut z_not_xor = vec![];
-    for (var3, expr) in exprs.iter() {
-        if var3.starts_with("z") && expr.op != Op::XOR {
-            z_not_xor.push(var3.clone());
-        }
-    }
-    log::debug!("{:?}", z_not_xor);
-
-    let mut middle = vec![];
-    for (var3, expr) in exprs.iter() {
-        if var3.starts_with("z") { continue }
-        if expr.var1.starts_with("x") || expr.var1.starts_with("y") { continue }
-        if expr.var2.starts_with("x") || expr.var2.starts_with("y") { continue }
-        if expr.op == Op::XOR {
-            middle.push(var3.clone());
-        }
-    }
-    log::debug!("{:?}", middle);
+    // In a ripple carry adder every gate has a fixed place, a gate which breaks one of these rules has its output swapped
+    let is_input = |var: &str| var.starts_with("x") || var.starts_with("y");
+    let is_first_bit = |expr: &Expr| is_input(&expr.var1) && expr.var1.ends_with("00");
+    let feeds = |var: &str, op: Op| exprs.values().any(|e| e.op == op && (e.var1 == var || e.var2 == var));
+    let last_z = exprs.keys().filter(|var| var.starts_with("z")).max().unwrap().clone();
 
-    log::debug!("********3");
+    let mut wrong = vec![];
     for (var3, expr) in exprs.iter() {
-        if expr.op == Op::AND {
-            for (var3b, exprb) in exprs.iter() {
-                if *var3b == *var3 { continue }
-                if *exprb.var1 != *var3 && *exprb.var2 != *var3 { continue }
-                if exprb.op == Op::OR { continue }
-                log::debug!("{var3} {var3b}");
+        let from_inputs = is_input(&expr.var1) && is_input(&expr.var2);
+        let is_wrong = if var3.starts_with("z") {
+            // Every output bit is a XOR, only the last one is the carry
+            if *var3 == last_z { expr.op != Op::OR } else { expr.op != Op::XOR }
+        } else {
+            match expr.op {
+                // A XOR of carries only produces an output bit
+                Op::XOR if !from_inputs => true,
+                // The XOR of two input bits goes on to the XOR with the carry
+                Op::XOR => !is_first_bit(expr) && !feeds(var3, Op::XOR),
+                // Both ANDs of a bit go into the OR which makes the carry
+                Op::AND => !is_first_bit(expr) && !feeds(var3, Op::OR),
+                Op::OR => false,
             }
-        }
+        };
+        if is_wrong { wrong.push(var3.clone()); }
     }
+    wrong.sort();
+    log::debug!("{:?}", wrong);
 
-    log::debug!("********4");
-    /*
-    If you have a XOR gate with inputs x, y, there must be another XOR
-    gate with this gate as an input. Search through all gates for an
-    XOR-gate with this gate as an input; if it does not exist, your
-    (original) XOR gate is faulty. 
-    */
-    for (var3, expr) in exprs.iter() {
-        if expr.op != Op::XOR { continue }
-        if !expr.var1.starts_with("x") && !expr.var1.starts_with("y") { continue }
-        if !expr.var2.starts_with("x") && !expr.var2.starts_with("y") { continue }
-        let mut has_xor = false;
-        for (var3b, exprb) in exprs.iter() {
-            if *var3b == *var3 { continue }
-            if *exprb.var1 == *var3 || *exprb.var2 == *var3 {
-                if exprb.op == Op::XOR { has_xor = true; }
-            }
-        }
-        if !has_xor {
-            log::debug!("\t{}", var3);
-        }
-    }
+    let result = wrong.join(",");
+    println!("Day 24 task 2 result is {}", result);
+    ctx.answer(2, result);
 }
 
 #[cfg(test)]
@@ -1,29 +1,26 @@
 use env_logger;
 use log;
-use itertools::Itertools;
+use std::path::Path;
 
-use utils;
+use utils::{self, parse, Context};
 
-fn get_data(filename: &str) -> (Vec<Vec<u8>>, Vec<Vec<u8>>) {
+fn get_data(filename: impl AsRef<Path>) -> (Vec<Vec<u8>>, Vec<Vec<u8>>) {
     let mut locks: Vec<Vec<u8>> = vec![];
     let mut keys: Vec<Vec<u8>> = vec![];
-    let mut is_lock: bool;
-    
-    let stream = utils::file_to_iter(filename);
-    for lines in &stream.chunks(8) {
-        is_lock = false;
+
+    let lines: Vec<String> = utils::file_to_iter(filename).collect();
+    for block in parse::blocks(&lines) {
+        block.expect_lines(7).unwrap();
+        if block.fields().any(|line| line.len() != 5) { panic!("{}", block.error("rows have to be 5 wide")) }
+
+        // The full top row marks a lock, the bottom row is full for keys and counts for neither
+        let is_lock = block.header().as_str() == "#####";
         let mut height_map: Vec<u8> = vec![0; 5];
-        for (row, line) in lines.enumerate() {
-            if row == 0 {
-                if line.contains("#####") { is_lock = true; }
-            }
-            else if row == 6 { continue }
-            else {
-                for (j, col) in line.chars().enumerate() {
-                    match col {
-                        '#'  => height_map[j] += 1,
-                        _ => (),
-                    }
+        for line in block.body().take(5) {
+            for (j, col) in line.chars().enumerate() {
+                match col {
+                    '#'  => height_map[j] += 1,
+                    _ => (),
                 }
             }
         }
@@ -52,10 +49,14 @@ fn count_fits(locks: &Vec<Vec<u8>>, keys: &Vec<Vec<u8>>) -> u32 {
     fit
 }
 
-pub fn main_day25_task1() {
+pub fn parse_input(ctx: &Context) {
+    ctx.keep_parsed(get_data(ctx.input()));
+}
+
+pub fn main_day25_task1(ctx: &Context) {
     _ = env_logger::builder().format_timestamp(None).try_init();
 
-    let (keys, locks) = get_data("day25\\data.txt");
+    let (keys, locks) = ctx.parsed(|ctx| get_data(ctx.input()));
     log::debug!("LOCKS");
     for l in locks.iter() {
         log::debug!("{:?}", l);
@@ -67,6 +68,7 @@ pub fn main_day25_task1() {
 
     let fit = count_fits(&locks, &keys);
     println!("Day 25 task 1 result is {}", fit);
+    ctx.answer(1, fit);
 }
 
 #[cfg(test)]
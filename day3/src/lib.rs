@@ -1,72 +1,278 @@
-use lazy_static::lazy_static;
-use regex::Regex;
-
-use utils;
-
-lazy_static!{
-    pub static ref MUL_REGEX: Regex = Regex::new(r"mul\(([0-9]{1,3}),([0-9]{1,3})\)").unwrap();
-    
-    // Sadly, RUST does not support lookback/lookahead
-    // don't() block until a do() or line ending
-    //pub static ref DONT_REGEX: Regex = Regex::new(r"don't\(\)(.+?)(?=do\(\)|$)").unwrap();
-    
-    pub static ref MUL_REGEX_STARTING_WITH_DO: Regex = Regex::new(r"do\(\)(.*)").unwrap();
-}
-
-fn get_muls(line: &str) -> u64 {
-    MUL_REGEX
-        .captures_iter(line)
-        .map(|caps| {
-            caps.get(1).unwrap().as_str().parse::<u64>().unwrap()
-            * caps.get(2).unwrap().as_str().parse::<u64>().unwrap()
-        })
-        .sum()
-}
-
-/*
-fn get_donts(line: &str) -> u64 {
-    DONT_REGEX
-        .captures_iter(line)
-        .map(|caps| caps.get(0).unwrap().as_str())
-        .map(|s| get_muls(s))
-        .sum()
-}
-        */
-
-pub fn main_day3_task1() {
-    let lines = utils::file_to_string_vector("day3\\data.txt");
-    let result: u64 = lines.iter().map(|line| get_muls(line)).sum();
-    println!("Day 3 task 1 result is {}", result);
+use std::io::{self, Read};
+
+use utils::Context;
+
+// Numbers have 1-3 digits
+const MAX_DIGITS: usize = 3;
+// Bytes read at a time
+const CHUNK_SIZE: usize = 1 << 16;
+
+/// A state the instructions work on
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct State {
+    pub enabled: bool,
+    pub sum: u64,
+}
+
+impl Default for State {
+    fn default() -> Self {
+        State { enabled: true, sum: 0 }
+    }
+}
+
+/// An instruction of the memory: its name, then `arity` numbers separated by commas in parentheses, e.g. mul(2,4)
+/// Names must not contain parentheses, commas or digits, see `Scanner`
+pub struct Instruction {
+    pub name: &'static str,
+    pub arity: usize,
+    pub execute: fn(&mut State, &[u64]),
+}
+
+pub const MUL: Instruction = Instruction {
+    name: "mul",
+    arity: 2,
+    execute: |state, args| if state.enabled { state.sum += args[0] * args[1] },
+};
+pub const DO: Instruction = Instruction { name: "do", arity: 0, execute: |state, _| state.enabled = true };
+pub const DONT: Instruction = Instruction { name: "don't", arity: 0, execute: |state, _| state.enabled = false };
+
+pub const TASK1: &[Instruction] = &[MUL];
+pub const TASK2: &[Instruction] = &[MUL, DO, DONT];
+
+/// A complete instruction found in the memory, `instruction` is the index in the table
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Token {
+    pub instruction: usize,
+    pub args: Vec<u64>,
+}
+
+enum Match {
+    // Can not become an instruction any more
+    No,
+    // Could still become an instruction with more bytes
+    Prefix,
+    Complete(Token),
+}
+
+// How far `text` matches the instruction, from its start to its end
+fn match_instruction(instruction: &Instruction, text: &[u8]) -> Match {
+    let name = instruction.name.as_bytes();
+    if text.len() <= name.len() {
+        return if name.starts_with(text) { Match::Prefix } else { Match::No }
+    }
+    if !text.starts_with(name) || text[name.len()] != b'(' { return Match::No }
+
+    let mut args = vec![];
+    let mut digits = 0;
+    let mut value = 0;
+    for &byte in text[name.len() + 1..].iter() {
+        match byte {
+            b'0'..=b'9' if digits < MAX_DIGITS && args.len() < instruction.arity => {
+                digits += 1;
+                value = value * 10 + (byte - b'0') as u64;
+            },
+            b',' if digits > 0 && args.len() + 1 < instruction.arity => {
+                args.push(value);
+                (digits, value) = (0, 0);
+            },
+            b')' if (digits > 0 && args.len() + 1 == instruction.arity) || (instruction.arity == 0 && digits == 0) => {
+                if digits > 0 { args.push(value); }
+                // The parenthesis is always the last byte, names do not contain one
+                return Match::Complete(Token { instruction: 0, args })
+            },
+            _ => return Match::No,
+        }
+    }
+    Match::Prefix
+}
+
+/// Finds the instructions of a table in corrupted memory, one byte at a time, anything else is skipped
+/// Only the bytes of a possible instruction are kept, at most as many as the longest instruction has,
+/// so the memory can come in pieces of any size
+///
+/// The kept bytes start at the leftmost possible instruction. When it fails, every later start in the kept
+/// bytes is tried, so names may overlap, e.g. mul and ul. A later start can not complete while the leftmost
+/// is still possible: that would need a name with a parenthesis, comma or digit, which `new` rejects.
+/// Of two instructions ending at the same byte the one starting first wins, e.g. don't() over t().
+pub struct Scanner<'a> {
+    table: &'a [Instruction],
+    pending: Vec<u8>,
+}
+
+impl<'a> Scanner<'a> {
+    pub fn new(table: &'a [Instruction]) -> Self {
+        for instruction in table {
+            let name = instruction.name;
+            assert!(!name.is_empty() && !name.contains(|c: char| "(),".contains(c) || c.is_ascii_digit()), "Invalid instruction name {name:?}");
+        }
+        Scanner { table, pending: vec![] }
+    }
+
+    fn check(&self) -> Match {
+        let mut result = Match::No;
+        for (i, instruction) in self.table.iter().enumerate() {
+            match match_instruction(instruction, &self.pending) {
+                Match::Complete(token) => return Match::Complete(Token { instruction: i, ..token }),
+                Match::Prefix => result = Match::Prefix,
+                Match::No => (),
+            }
+        }
+        result
+    }
+
+    /// The instruction ending with this byte, if there is one
+    pub fn push(&mut self, byte: u8) -> Option<Token> {
+        self.pending.push(byte);
+        // Without a match, the next instruction can start at any of the following bytes
+        while !self.pending.is_empty() {
+            match self.check() {
+                Match::No => { self.pending.remove(0); },
+                Match::Prefix => return None,
+                Match::Complete(token) => {
+                    self.pending.clear();
+                    return Some(token)
+                },
+            }
+        }
+        None
+    }
+
+    pub fn tokens(mut self, memory: &[u8]) -> Vec<Token> {
+        memory.iter().filter_map(|byte| self.push(*byte)).collect()
+    }
 }
 
-/*
-pub fn main_day3_task2() {
-    let content = utils::file_to_string_vector("day3\\data.txt").concat();
-    let muls: u64 = get_muls(content);
-    let donts: u64 = get_donts(content);
-    println!("Day 3 task 2 result is {}", muls - donts);
-}
-*/
-
-pub fn main_day3_task2() {
-    //Merge the whole file because a don't() can span multiple lines
-    let content = utils::file_to_string_vector("day3\\data.txt").concat();
-    let mut result = 0;
-    
-    //Split on dont'()
-    let splitted = content.split("don't()").collect::<Vec<&str>>();
-    
-    //Take everything from the first split
-    result += get_muls(splitted[0]);
-    
-    //In the remaining splits, find the first do(), and take everything after
-    for s in splitted.iter().skip(1) {
-        result += MUL_REGEX_STARTING_WITH_DO
-            .find_iter(s)
-            .map(|m| m.as_str())
-            .map(|m| get_muls(m))
-            .sum::<u64>();
+/// Executes the instructions of the memory as the scanner finds them
+pub struct Interpreter<'a> {
+    scanner: Scanner<'a>,
+    pub state: State,
+}
+
+impl<'a> Interpreter<'a> {
+    pub fn new(table: &'a [Instruction]) -> Self {
+        Interpreter { scanner: Scanner::new(table), state: State::default() }
+    }
+
+    pub fn feed(&mut self, memory: &[u8]) {
+        for byte in memory.iter() {
+            if let Some(token) = self.scanner.push(*byte) {
+                (self.scanner.table[token.instruction].execute)(&mut self.state, &token.args);
+            }
+        }
     }
-    
+
+    /// Feeds everything from `reader` in chunks of `chunk_size` bytes, which must not be 0
+    /// The interpreter itself uses memory independent of the input size, the reader might not: `Context::reader`
+    /// streams files on disk, but embedded and encrypted inputs are held in memory as a whole
+    /// Line breaks are not part of the memory, an instruction can go on in the next line
+    pub fn run(&mut self, mut reader: impl Read, chunk_size: usize) -> io::Result<()> {
+        if chunk_size == 0 { return Err(io::Error::new(io::ErrorKind::InvalidInput, "the chunk size must not be 0")) }
+        let mut chunk = vec![0; chunk_size];
+        loop {
+            let n = match reader.read(&mut chunk) {
+                Ok(0) => return Ok(()),
+                Ok(n) => n,
+                Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+                Err(e) => return Err(e),
+            };
+            for part in chunk[..n].split(|byte| *byte == b'\n' || *byte == b'\r') {
+                self.feed(part);
+            }
+        }
+    }
+}
+
+/// The state after running all the memory of `reader`
+pub fn evaluate(reader: impl Read, table: &[Instruction], chunk_size: usize) -> io::Result<State> {
+    let mut interpreter = Interpreter::new(table);
+    interpreter.run(reader, chunk_size)?;
+    Ok(interpreter.state)
+}
+
+fn run(ctx: &Context, table: &[Instruction]) -> u64 {
+    let state = evaluate(ctx.reader(), table, ctx.param("chunk_size", CHUNK_SIZE));
+    state.unwrap_or_else(|e| panic!("Could not read {}: {e}", ctx.input().display())).sum
+}
+
+// The tasks stream the memory and any bytes are valid memory, so there is nothing to parse ahead of them,
+// only the input has to be there
+pub fn parse_input(ctx: &Context) {
+    ctx.reader();
+}
+
+pub fn main_day3_task1(ctx: &Context) {
+    let result = run(ctx, TASK1);
     println!("Day 3 task 1 result is {}", result);
+    ctx.answer(1, result);
+}
+
+pub fn main_day3_task2(ctx: &Context) {
+    let result = run(ctx, TASK2);
+    println!("Day 3 task 2 result is {}", result);
+    ctx.answer(2, result);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sum(table: &[Instruction], memory: &str) -> u64 {
+        let mut interpreter = Interpreter::new(table);
+        interpreter.feed(memory.as_bytes());
+        interpreter.state.sum
+    }
+
+    #[test]
+    fn test_interpreter() {
+        assert_eq!(sum(TASK1, "xmul(2,4)%&mul[3,7]!@^do_not_mul(5,5)+mul(32,64]then(mul(11,8)mul(8,5))"), 161);
+        assert_eq!(sum(TASK2, "xmul(2,4)&mul[3,7]!^don't()_mul(5,5)+mul(32,64](mul(11,8)undo()?mul(8,5))"), 48);
+        assert_eq!(sum(TASK1, "mul(1234,5)mul(12,5 )mul(,5)mumul(2,3)"), 6);
+        assert_eq!(sum(TASK2, "dodon't()mul(2,3)do(mul(1,1)do()mul(4,5)"), 20);
+
+        let tokens = Scanner::new(TASK2).tokens(b"don't()mul(2,3)do()");
+        assert_eq!(tokens, vec![
+            Token { instruction: 2, args: vec![] },
+            Token { instruction: 0, args: vec![2, 3] },
+            Token { instruction: 1, args: vec![] },
+        ]);
+
+        // New instructions only need an entry in the table
+        const ADD: Instruction = Instruction { name: "add", arity: 3, execute: |state, args| state.sum += args.iter().sum::<u64>() };
+        assert_eq!(sum(&[MUL, ADD], "add(1,2,3)mul(2,2)add(1,2)"), 10);
+    }
+
+    #[test]
+    fn test_overlapping_names() {
+        const UL: Instruction = Instruction { name: "ul", arity: 1, execute: |state, args| state.sum += 100 * args[0] };
+        const T: Instruction = Instruction { name: "t", arity: 0, execute: |state, _| state.sum += 1000 };
+        // mul fails on the single argument, ul starting inside it does not
+        assert_eq!(sum(&[MUL, UL], "mul(5)mul(2,3)mumul(4)"), 906);
+        // Both end at the same parenthesis, don't starts first
+        assert_eq!(sum(&[DONT, T], "don't()don'xt()"), 1000);
+    }
+
+    #[test]
+    #[should_panic(expected = "Invalid instruction name")]
+    fn test_invalid_name() {
+        const F: Instruction = Instruction { name: "f(", arity: 0, execute: |_, _| () };
+        Scanner::new(&[F]);
+    }
+
+    #[test]
+    fn test_chunks() {
+        let memory = "xmul(2,4)&mul[3,7]!^don'\nt()_mul(5,5)+mul(32,64](mul(11,8)un\r\ndo()?mul(8,\n5))mul(1";
+        let expected = evaluate(memory.replace(['\n', '\r'], "").as_bytes(), TASK2, 1000).unwrap();
+        assert_eq!(expected.sum, 48);
+        for chunk_size in 1..=memory.len() {
+            assert_eq!(evaluate(memory.as_bytes(), TASK2, chunk_size).unwrap(), expected);
+        }
+        assert_eq!(evaluate(memory.as_bytes(), TASK2, 0).unwrap_err().kind(), io::ErrorKind::InvalidInput);
+
+        // Only the start of a possible instruction is kept
+        let mut scanner = Scanner::new(TASK2);
+        for byte in "mul(123,456".bytes().cycle().take(100_000) {
+            scanner.push(byte);
+            assert!(scanner.pending.len() <= "mul(123,456)".len());
+        }
+    }
 }
@@ -1,4 +1,4 @@
-use utils;
+use utils::{self, Context};
 
 fn move_dir(pos: usize, increase: Option<bool>, distance: usize, limit: usize) -> Option<usize> {
     //Helper function to stay in usize all the way
@@ -39,10 +39,10 @@ fn check_word(
     )
 }
 
-fn get_letters() -> Vec<Vec<char>> {
+fn get_letters(ctx: &Context) -> Vec<Vec<char>> {
     //Read into a 2d array
     let mut letters = vec![];
-    for (i, line) in utils::file_to_iter("day4\\data.txt").enumerate() {
+    for (i, line) in ctx.lines().enumerate() {
         letters.push(vec![]);
         for char in line.chars() {
             letters[i].push(char);
@@ -51,8 +51,12 @@ fn get_letters() -> Vec<Vec<char>> {
     letters
 }
 
-pub fn main_day4_task1() {
-    let letters = get_letters();
+pub fn parse_input(ctx: &Context) {
+    ctx.keep_parsed(get_letters(ctx));
+}
+
+pub fn main_day4_task1(ctx: &Context) {
+    let letters = ctx.parsed(get_letters);
     let x_dim = letters.len();
     let y_dim = letters[0].len();
 
@@ -74,10 +78,11 @@ pub fn main_day4_task1() {
         }
     }
     println!("Day 4 task 1 result is {}", result);
+    ctx.answer(1, result);
 }
 
-pub fn main_day4_task2() {
-    let letters = get_letters();
+pub fn main_day4_task2(ctx: &Context) {
+    let letters = ctx.parsed(get_letters);
     let mut result = 0;
 
     for i in 0..letters.len()-2 {
@@ -92,4 +97,5 @@ pub fn main_day4_task2() {
     }
     
     println!("Day 4 task 2 result is {}", result);
+    ctx.answer(2, result);
 }
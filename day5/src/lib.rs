@@ -1,13 +1,14 @@
 use std::collections::{HashMap, HashSet};
 
-use utils;
+use utils::Context;
 
-const FILENAME: &str = "day5\\data.txt";
+/// The pages which have to come after a page
+pub type Rules = HashMap<u64, HashSet<u64>>;
 
-fn get_rules() -> HashMap<u64, HashSet<u64>> {
+fn get_rules(ctx: &Context) -> Rules {
     let mut rules: HashMap<u64, HashSet<u64>> = HashMap::new();
 
-    for line in utils::file_to_iter(FILENAME) {
+    for line in ctx.lines() {
         if line.contains('|') {
             let values = line
                 .split('|')
@@ -23,10 +24,10 @@ fn get_rules() -> HashMap<u64, HashSet<u64>> {
     rules
 }
 
-fn get_pages() -> Vec<Vec<u64>> {
+fn get_pages(ctx: &Context) -> Vec<Vec<u64>> {
     let mut pages: Vec<Vec<u64>> = vec![];
 
-    for line in utils::file_to_iter(FILENAME) {
+    for line in ctx.lines() {
         if line.contains(',') {
             pages.push(line.split(',').map(|v| v.parse::<u64>().unwrap()).collect());
         }
@@ -35,6 +36,10 @@ fn get_pages() -> Vec<Vec<u64>> {
     pages
 }
 
+fn get_data(ctx: &Context) -> (Rules, Vec<Vec<u64>>) {
+    (get_rules(ctx), get_pages(ctx))
+}
+
 fn order_page(rules: &HashMap<u64, HashSet<u64>>, page: &Vec<u64>) -> Vec<u64> {
     let mut ordered_page = vec![];
 
@@ -62,9 +67,13 @@ fn order_page(rules: &HashMap<u64, HashSet<u64>>, page: &Vec<u64>) -> Vec<u64> {
     ordered_page
 }
 
-pub fn main_day5_task1() -> Vec<Vec<u64>> {
-    let rules = get_rules();
-    let pages = get_pages();
+pub fn parse_input(ctx: &Context) {
+    ctx.keep_parsed(get_data(ctx));
+}
+
+/// Returns the rules and the wrongly ordered pages for task 2
+pub fn main_day5_task1(ctx: &Context) -> (Rules, Vec<Vec<u64>>) {
+    let (rules, pages) = ctx.parsed(get_data);
     let mut result = 0;
     let mut is_wrong: bool;
     let mut wrong_pages = vec![];
@@ -89,16 +98,17 @@ pub fn main_day5_task1() -> Vec<Vec<u64>> {
     }
 
     println!("Day 5 task 1 result is {}", result);
+    ctx.answer(1, result);
 
-    wrong_pages
+    (rules, wrong_pages)
 }
 
-pub fn main_day5_task2(wrong_pages: &Vec<Vec<u64>>) {
-    let rules = get_rules();
+pub fn main_day5_task2(ctx: &Context, rules: &Rules, wrong_pages: &Vec<Vec<u64>>) {
     let result: u64 = wrong_pages
         .iter()
-        .map(|page| order_page(&rules, page)[(page.len() - 1) / 2])
+        .map(|page| order_page(rules, page)[(page.len() - 1) / 2])
         .sum();
 
     println!("Day 5 task 2 result is {}", result);
+    ctx.answer(2, result);
 }
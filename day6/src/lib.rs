@@ -3,9 +3,9 @@ use std::collections::HashSet;
 use env_logger;
 use log;
 
-use utils;
+use utils::{simulation::{Outcome, Simulation}, Context};
 
-#[derive(Clone, Copy, PartialEq)]
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
 enum Direction {
     Up,
     Down,
@@ -42,7 +42,7 @@ impl Position {
         else { false }
     }
     
-    fn move_next(&mut self, maze: &Vec<Vec<u8>>, visited: &mut Vec<Vec<Vec<Direction>>>) -> bool {
+    fn move_next(&mut self, maze: &[Vec<u8>]) -> bool {
         if self.out_of_bounds_on_next() { return false; }
         match self.direction {
             Direction::Up => {
@@ -74,17 +74,74 @@ impl Position {
                 }
             },
         }
-        if visited[self.x][self.y].contains(&self.direction) { return false; }
-        else { visited[self.x][self.y].push(self.direction) }
-
         true
     }
 }
 
-fn get_maze() -> (usize, usize, Vec<Vec<u8>>) {
+struct Guard<'a> {
+    maze: &'a [Vec<u8>],
+    pos: Position,
+}
+
+impl<'a> Guard<'a> {
+    fn new(maze: &'a [Vec<u8>], start_x: usize, start_y: usize) -> Self {
+        let pos = Position{ x: start_x, y: start_y, direction: Direction::Up, x_limit: maze.len(), y_limit: maze[0].len() };
+        Guard { maze, pos }
+    }
+}
+
+impl Simulation for Guard<'_> {
+    type State = (usize, usize, Direction);
+
+    fn step(&mut self) -> bool {
+        self.pos.move_next(self.maze)
+    }
+
+    fn state(&self) -> Self::State {
+        (self.pos.x, self.pos.y, self.pos.direction)
+    }
+}
+
+// Positions the guard visits until leaving the maze, together with how the walk ended
+fn walk(maze: &[Vec<u8>], start_x: usize, start_y: usize) -> (HashSet<(usize, usize)>, Outcome) {
+    let mut visited = HashSet::new();
+    let mut guard = Guard::new(maze, start_x, start_y);
+    // Every position in every direction, more steps can only mean a loop
+    let limit = 4 * maze.len() * maze[0].len();
+    let outcome = guard.run_until(
+        |guard| {
+            log::debug!("Position = {} {}", guard.pos.x, guard.pos.y);
+            visited.insert((guard.pos.x, guard.pos.y));
+            false
+        },
+        Some(limit),
+    );
+    (visited, outcome)
+}
+
+// Walls as '#', visited positions as 'X' and the given extra positions as 'O'
+fn render_path(maze: &[Vec<u8>], visited: &HashSet<(usize, usize)>, extra: &HashSet<(usize, usize)>) -> Vec<String> {
+    maze
+        .iter()
+        .enumerate()
+        .map(|(x, line)| line
+            .iter()
+            .enumerate()
+            .map(|(y, v)| {
+                if *v == 1 { '#' }
+                else if extra.contains(&(x, y)) { 'O' }
+                else if visited.contains(&(x, y)) { 'X' }
+                else { '.' }
+            })
+            .collect::<String>()
+        )
+        .collect()
+}
+
+fn get_maze(ctx: &Context) -> (usize, usize, Vec<Vec<u8>>) {
     let mut start_x: usize = 0;
     let mut start_y: usize = 0;
-    let maze: Vec<Vec<u8>> = utils::file_to_iter("day6\\data.txt")
+    let maze: Vec<Vec<u8>> = ctx.lines()
         .enumerate()
         .map(|(i, line)| line
             .chars()
@@ -107,57 +164,54 @@ fn get_maze() -> (usize, usize, Vec<Vec<u8>>) {
     (start_x, start_y, maze)
 }
 
-pub fn main_day6_task1() {
+pub fn parse_input(ctx: &Context) {
+    ctx.keep_parsed(get_maze(ctx));
+}
+
+pub fn main_day6_task1(ctx: &Context) {
     let _ = env_logger::try_init();
 
-    let (start_x, start_y, maze) = get_maze();
+    let (start_x, start_y, maze) = ctx.parsed(get_maze);
     
     log::debug!("Start: {} {}", start_x, start_y);
     for line in &maze {
         log::debug!("{:?}", line);
     }
 
-    let mut visited: Vec<Vec<Vec<Direction>>> = vec![vec![vec![]; maze[0].len()]; maze.len()];
-    visited[start_x][start_y].push(Direction::Up);
-    let mut pos = Position{ x: start_x, y: start_y, direction: Direction::Up, x_limit: maze.len(), y_limit: maze[0].len() };
-
-    while pos.move_next(&maze, &mut visited) {
-        log::debug!("Position = {} {}", pos.x, pos.y);
-    }
+    let (visited, outcome) = walk(&maze, start_x, start_y);
+    if !matches!(outcome, Outcome::Finished(_)) { panic!("The guard never leaves the maze: {:?}", outcome); }
 
-    let result: u64 = visited
-        .iter()
-        .map(|line| line
-            .iter()
-            .map(|directions| if directions.is_empty() { 0 } else { 1 })
-            .sum::<u64>()
-        ).sum();
+    let result = visited.len();
     println!("Day 6 task 1 result is {:?}", result);
+    ctx.answer(1, result);
+    ctx.grid("Guard path", render_path(&maze, &visited, &HashSet::new()));
 }
 
-pub fn main_day6_task2() {
+pub fn main_day6_task2(ctx: &Context) {
     let _ = env_logger::try_init();
 
-    let (start_x, start_y, maze) = get_maze();
-    let mut visited: Vec<Vec<Vec<Direction>>> = vec![vec![vec![]; maze[0].len()]; maze.len()];
-    let mut pos = Position{ x: start_x, y: start_y, direction: Direction::Up, x_limit: maze.len(), y_limit: maze[0].len() };
+    let (start_x, start_y, maze) = ctx.parsed(get_maze);
+    let mut visited: HashSet<(usize, usize)> = HashSet::new();
     let mut loops: HashSet<(usize, usize)> = HashSet::new();
-    while pos.move_next(&maze, &mut visited) {
+    let mut guard = Guard::new(&maze, start_x, start_y);
+    while guard.step() {
+        let (x, y) = (guard.pos.x, guard.pos.y);
+        // The guard comes by some positions several times, the obstruction there is only tried once
+        if !visited.insert((x, y)) { continue }
+
         let mut temp_maze = maze.clone();
-        temp_maze[pos.x][pos.y] = 1;
+        temp_maze[x][y] = 1;
         for line in &temp_maze {
             log::debug!("{:?}", line);
         }
-        let mut temp_visited: Vec<Vec<Vec<Direction>>> = vec![vec![vec![]; maze[0].len()]; maze.len()];
-        let mut temp_pos = Position{ x: start_x, y: start_y, direction: Direction::Up, x_limit: maze.len(), y_limit: maze[0].len() };
-        while temp_pos.move_next(&temp_maze, &mut temp_visited) {
-            log::debug!("Position = {} {}", temp_pos.x, temp_pos.y);
-        }
-        if !temp_pos.out_of_bounds_on_next() {
-            log::info!("Loop found for {} {}", pos.x, pos.y);
-            loops.insert((pos.x, pos.y));
+        let outcome = Guard::new(&temp_maze, start_x, start_y).find_cycle(None);
+        if let Outcome::Cycle { start, length } = outcome {
+            log::info!("Loop found for {} {}, starting after {} steps with length {}", x, y, start, length);
+            loops.insert((x, y));
         }
     }
     log::info!("{:?}", loops);
     println!("Day 6 task 2 result is {:?}", loops.len());
+    ctx.answer(2, loops.len());
+    ctx.grid("Obstructions causing a loop", render_path(&maze, &visited, &loops));
 }
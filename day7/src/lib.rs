@@ -1,38 +1,30 @@
 use itertools::Itertools;
-use regex::Regex;
+use lazy_static::lazy_static;
 
-use utils;
+use utils::checked::{self, Origin};
+use utils::Context;
+use utils::parse::{self, Template};
 
-fn get_data() -> Vec<(u64, Vec<u64>)> {
-    let mut data: Vec<(u64, Vec<u64>)> = vec![];
-    let re = Regex::new(r"\d+").unwrap();
-    for line in utils::file_to_iter("day7\\data.txt") {
-        for (i, part) in line.split(":").enumerate() {
-            match i {
-                0 => data.push((part.parse::<u64>().unwrap(), vec![])),
-                1 => {
-                    if let Some((_, last)) = data.last_mut() {
-                        *last = re
-                            .find_iter(part)
-                            .map(|nr| nr.as_str().parse::<u64>().unwrap())
-                            .collect::<Vec<u64>>();
-                        }
-                },
-                _ => (),
-            }
-        }
-    }
-    data
+lazy_static!{
+    //190: 10 19
+    pub static ref EQUATION: Template = Template::new("{uint}: {}");
 }
 
-fn is_correct_part1(expected: u64, numbers: &[u64]) -> bool {
+fn get_data(ctx: &Context) -> Vec<(u64, Vec<u64>)> {
+    parse::lines(ctx.lines(), |line| {
+        let caps = EQUATION.captures(line)?;
+        Ok((caps[0].parse::<u64>()?, caps[1].list::<u64>(" ")?))
+    }).unwrap()
+}
+
+fn is_correct_part1(expected: u64, numbers: &[u64], origin: Origin) -> bool {
     let a = vec!['*', '+'];
     for ops in (1..numbers.len()).map(|_| a.iter()).multi_cartesian_product()  {
         let mut result = numbers[0];
         for (i, op) in ops.iter().enumerate() {
             match op {
-                '*' => result *= numbers[i+1],
-                '+' => result += numbers[i+1],
+                '*' => result = checked::mul(result, numbers[i+1], origin),
+                '+' => result = checked::add(result, numbers[i+1], origin),
                 _ => (),
             }
         }
@@ -41,15 +33,18 @@ fn is_correct_part1(expected: u64, numbers: &[u64]) -> bool {
     false
 }
 
-fn is_correct_part2(expected: u64, numbers: &[u64]) -> bool {
+fn is_correct_part2(expected: u64, numbers: &[u64], origin: Origin) -> bool {
     let a = vec!["*", "+", "||"];
     for ops in (1..numbers.len()).map(|_| a.iter()).multi_cartesian_product()  {
         let mut result = numbers[0];
         for (i, &&op) in ops.iter().enumerate() {
             match op {
-                "*" => result *= numbers[i+1],
-                "+" => result += numbers[i+1],
-                "||" => result = result * (10 as u64).pow(numbers[i+1].ilog(10) + 1) + numbers[i+1],
+                "*" => result = checked::mul(result, numbers[i+1], origin),
+                "+" => result = checked::add(result, numbers[i+1], origin),
+                "||" => {
+                    let shift = checked::pow(10_u64, numbers[i+1].ilog(10) + 1, origin);
+                    result = checked::add(checked::mul(result, shift, origin), numbers[i+1], origin);
+                },
                 _ => panic!("Invalid operator"),
             }
         }
@@ -58,22 +53,36 @@ fn is_correct_part2(expected: u64, numbers: &[u64]) -> bool {
     false
 }
 
-pub fn main_day7_task1() {
-    let data = get_data();
+pub fn parse_input(ctx: &Context) {
+    ctx.keep_parsed(get_data(ctx));
+}
+
+pub fn main_day7_task1(ctx: &Context) {
+    let data = ctx.parsed(get_data);
     
-    let result: u64 = data
+    let result: u64 = checked::sum(data
         .iter()
-        .map(|(expected, numbers)| if is_correct_part1(*expected, &numbers) { *expected } else { 0 })
-        .sum();
+        .enumerate()
+        .map(|(i, (expected, numbers))| {
+            let origin = Origin::new(7, i + 1);
+            (if is_correct_part1(*expected, numbers, origin) { *expected } else { 0 }, origin)
+        })
+    );
     println!("Day 7 task 1 result is {}", result);
+    ctx.answer(1, result);
 }
 
-pub fn main_day7_task2() {
-    let data = get_data();
+pub fn main_day7_task2(ctx: &Context) {
+    let data = ctx.parsed(get_data);
     
-    let result: u64 = data
+    let result: u64 = checked::sum(data
         .iter()
-        .map(|(expected, numbers)| if is_correct_part2(*expected, &numbers) { *expected } else { 0 })
-        .sum();
+        .enumerate()
+        .map(|(i, (expected, numbers))| {
+            let origin = Origin::new(7, i + 1);
+            (if is_correct_part2(*expected, numbers, origin) { *expected } else { 0 }, origin)
+        })
+    );
     println!("Day 7 task 2 result is {}", result);
+    ctx.answer(2, result);
 }
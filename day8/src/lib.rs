@@ -4,28 +4,33 @@ use std::cmp::{max, min};
 use env_logger;
 use log;
 
-use utils;
+use utils::Context;
+use utils::number_theory;
 
-const FILENAME: &str = "day8\\data.txt";
-
-fn get_data() -> HashMap<char, Vec<(usize, usize)>> {
+// The antennas by frequency and the size of the square map
+fn get_data(ctx: &Context) -> (HashMap<char, Vec<(usize, usize)>>, usize) {
     let mut antennas = HashMap::new();
+    let mut size = 0;
 
-    for (i, line) in utils::file_to_iter(FILENAME).enumerate() {
+    for (i, line) in ctx.lines().enumerate() {
+        if i == 0 { size = line.len(); }
         for (j, c) in line.chars().enumerate() {
             if c == '.' { continue }
             antennas.entry(c).or_insert(vec![]).push((i, j));
         }
     }
 
-    antennas
+    (antennas, size)
+}
+
+pub fn parse_input(ctx: &Context) {
+    ctx.keep_parsed(get_data(ctx));
 }
 
-pub fn main_day8_task1() {
+pub fn main_day8_task1(ctx: &Context) {
     let _ = env_logger::try_init();
 
-    let antennas = get_data();
-    let size = utils::file_to_iter(FILENAME).next().unwrap().len();
+    let (antennas, size) = ctx.parsed(get_data);
     let mut antinodes: HashSet<(usize, usize)> = HashSet::new();
 
     for (_, v) in antennas.iter() {
@@ -48,13 +53,13 @@ pub fn main_day8_task1() {
     }
 
     println!("Day 8 task 1 result is {}", antinodes.len());
+    ctx.answer(1, antinodes.len());
 }
 
-pub fn main_day8_task2() {
+pub fn main_day8_task2(ctx: &Context) {
     let _ = env_logger::try_init();
 
-    let antennas = get_data();
-    let size = utils::file_to_iter(FILENAME).next().unwrap().len();
+    let (antennas, size) = ctx.parsed(get_data);
     let mut antinodes: HashSet<(usize, usize)> = HashSet::new();
 
     for (_, v) in antennas.iter() {
@@ -65,6 +70,9 @@ pub fn main_day8_task2() {
                 let p2 = v[j];
                 let x_distance = max(p1.0, p2.0) - min(p1.0, p2.0);
                 let y_distance = max(p1.1, p2.1) - min(p1.1, p2.1);
+                // Every grid point on the line counts, also those between the antennas
+                let g = number_theory::gcd(x_distance as i128, y_distance as i128) as usize;
+                let (x_distance, y_distance) = (x_distance / g, y_distance / g);
                 log::debug!("\t{} {} {} {} {} {}", p1.0, p1.1, p2.0, p2.1, x_distance, y_distance);
 
                 if (p1.0 > p2.0 && p1.1 > p2.1) || (p1.0 < p2.0 && p1.1 < p2.1) {
@@ -110,4 +118,5 @@ pub fn main_day8_task2() {
     }
 
     println!("Day 8 task 2 result is {}", antinodes.len());
+    ctx.answer(2, antinodes.len());
 }
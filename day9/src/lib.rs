@@ -1,20 +1,28 @@
 use env_logger;
 use log;
 
-use utils;
+use utils::Context;
 
-const FILENAME: &str = "day9\\data.txt";
 const RADIX: u32 = 10;
 
-pub fn main_day9_task1() {
-    _ = env_logger::try_init();
-
-    let line: Vec<u32> = utils::file_to_iter(FILENAME)
+// The disk map, the sizes of files and free spaces alternating
+fn get_data(ctx: &Context) -> Vec<u32> {
+    ctx.lines()
         .next()
         .unwrap()
         .chars()
         .map(|c| c.to_digit(RADIX).unwrap())
-        .collect();
+        .collect()
+}
+
+pub fn parse_input(ctx: &Context) {
+    ctx.keep_parsed(get_data(ctx));
+}
+
+pub fn main_day9_task1(ctx: &Context) {
+    _ = env_logger::try_init();
+
+    let line = ctx.parsed(get_data);
 
     let mut position = 0;
     let mut is_front_file = true;
@@ -65,17 +73,13 @@ pub fn main_day9_task1() {
     }
 
     println!("Day 9 task 1 result is {}", result);
+    ctx.answer(1, result);
 }
 
-pub fn main_day9_task2() {
+pub fn main_day9_task2(ctx: &Context) {
     _ = env_logger::try_init();
 
-    let line: Vec<u32> = utils::file_to_iter(FILENAME)
-        .next()
-        .unwrap()
-        .chars()
-        .map(|c| c.to_digit(RADIX).unwrap())
-        .collect();
+    let line = ctx.parsed(get_data);
     
     // Define how big is the free space, and how much it is already occupied (starts all with 0)
     let mut free_space_map: Vec<(u32, u32)> = line.iter().skip(1).step_by(2).map(|v| (*v, 0)).collect();
@@ -132,4 +136,5 @@ pub fn main_day9_task2() {
         .sum();
 
     println!("Day 9 task 2 result is {}", result);
+    ctx.answer(2, result);
 }
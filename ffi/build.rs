@@ -0,0 +1,15 @@
+// Generates aoc2024.h from the extern "C" functions in src/lib.rs into OUT_DIR
+// The copy in include/ is kept up to date by hand, the build does not write to the source tree
+
+use std::{env, path::PathBuf};
+
+fn main() {
+    println!("cargo:rerun-if-changed=src/lib.rs");
+    println!("cargo:rerun-if-changed=cbindgen.toml");
+
+    let crate_dir = PathBuf::from(env::var("CARGO_MANIFEST_DIR").unwrap());
+    let config = cbindgen::Config::from_file(crate_dir.join("cbindgen.toml")).unwrap();
+    cbindgen::generate_with_config(&crate_dir, config)
+        .expect("Could not generate the C header")
+        .write_to_file(PathBuf::from(env::var("OUT_DIR").unwrap()).join("aoc2024.h"));
+}
@@ -0,0 +1,57 @@
+/* Generated by cbindgen from ffi/src/lib.rs, do not edit */
+
+#ifndef AOC2024_H
+#define AOC2024_H
+
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+#include <stdlib.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+// Writes the numbers of the available days to `days`, at most `capacity` of them,
+// and returns how many days there are
+//
+// # Safety
+// `days` must point to `capacity` writable bytes, it may be NULL if `capacity` is 0
+size_t aoc_days(uint8_t *days, size_t capacity);
+
+// The number of parts of a day, 0 if the day is not available
+uint8_t aoc_parts(uint8_t day);
+
+// Solves a part of a day for the puzzle input in `input`, with the default parameters
+// Returns the answer, or NULL on failure with the reason available from `aoc_last_error`
+//
+// # Safety
+// `input` must point to `len` readable bytes
+char *aoc_solve(uint8_t day, uint8_t part, const uint8_t *input, size_t len);
+
+// Like `aoc_solve`, with the parameters of the day in `params`, one `name=value` per line,
+// e.g. "width=11\nheight=7" for the sample of day 14
+//
+// # Safety
+// `input` must point to `len` readable bytes, `params` must be NULL or a NUL terminated string
+char *aoc_solve_params(uint8_t day,
+                       uint8_t part,
+                       const uint8_t *input,
+                       size_t len,
+                       const char *params);
+
+// The reason of the last failure on the calling thread, NULL if there was none
+char *aoc_last_error(void);
+
+// Releases a string returned by the library, NULL is ignored
+//
+// # Safety
+// `s` must come from this library and must not be used afterwards
+void aoc_free_string(char *s);
+
+#ifdef __cplusplus
+}  // extern "C"
+#endif  // __cplusplus
+
+#endif  /* AOC2024_H */
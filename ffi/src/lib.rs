@@ -0,0 +1,189 @@
+//! C API of the solvers, the header is include/aoc2024.h
+//!
+//! The build generates the header into its output directory, a test checks that include/aoc2024.h is up to date.
+//! Parameters from the config of the main binary are not applied, e.g. the grid size of day 14 or the number
+//! of bytes of day 18 for the sample inputs, they are passed to `aoc_solve_params` instead.
+//! Strings returned by the library belong to the caller and are released with `aoc_free_string`.
+//! The solvers still print their usual output to stdout.
+
+use std::{
+    cell::RefCell,
+    ffi::{c_char, CStr, CString},
+    panic::{self, AssertUnwindSafe},
+    ptr, slice,
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+use utils::Context;
+
+thread_local! {
+    static LAST_ERROR: RefCell<Option<String>> = const { RefCell::new(None) };
+}
+
+// Every input buffer gets its own file name, so solves on several threads do not mix up their inputs
+static NEXT_INPUT: AtomicUsize = AtomicUsize::new(0);
+
+fn to_c_string(s: String) -> *mut c_char {
+    // An answer never contains a NUL, an error message might quote one from the input
+    CString::new(s.replace('\0', "\\0")).unwrap().into_raw()
+}
+
+// Parameters as one `name=value` per line, like `--set` of the main binary
+fn parse_params(params: &str) -> Result<Vec<(&str, &str)>, String> {
+    params
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| line.split_once('=').ok_or(format!("Invalid parameter: {line}")))
+        .collect()
+}
+
+fn solve(day: u8, part: u8, input: &[u8], params: &str) -> Result<String, String> {
+    let day = solvers::get_day(day).ok_or(format!("Day {day} is not available"))?;
+    let task = day.task(part).ok_or(format!("Day {} has no part {part}", day.number))?;
+    let params = parse_params(params)?;
+    let content = String::from_utf8(input.to_vec()).map_err(|e| format!("The input is not UTF-8: {e}"))?;
+
+    let name = format!("<input {}>", NEXT_INPUT.fetch_add(1, Ordering::Relaxed));
+    utils::embedded::add(&name, content);
+    let mut ctx = Context::new(&name);
+    for (param, value) in params {
+        ctx.set_param(param, value);
+    }
+    let result = panic::catch_unwind(AssertUnwindSafe(|| (task.run)(&ctx)));
+    utils::embedded::remove(&name);
+    if result.is_err() { return Err(format!("Day {} part {part} failed on the input", day.number)) }
+
+    ctx.take_output()
+        .answers
+        .into_iter()
+        .find(|(p, _)| *p == part)
+        .map(|(_, answer)| answer)
+        .ok_or(format!("Day {} part {part} gave no answer", day.number))
+}
+
+/// Writes the numbers of the available days to `days`, at most `capacity` of them,
+/// and returns how many days there are
+///
+/// # Safety
+/// `days` must point to `capacity` writable bytes, it may be NULL if `capacity` is 0
+#[no_mangle]
+pub unsafe extern "C" fn aoc_days(days: *mut u8, capacity: usize) -> usize {
+    let numbers: Vec<u8> = solvers::days().iter().map(|day| day.number).collect();
+    if !days.is_null() {
+        for (i, number) in numbers.iter().take(capacity).enumerate() {
+            *days.add(i) = *number;
+        }
+    }
+    numbers.len()
+}
+
+/// The number of parts of a day, 0 if the day is not available
+#[no_mangle]
+pub extern "C" fn aoc_parts(day: u8) -> u8 {
+    match solvers::get_day(day) {
+        Some(day) => day.tasks.iter().flat_map(|task| task.parts.iter()).copied().max().unwrap_or(0),
+        None => 0,
+    }
+}
+
+/// Solves a part of a day for the puzzle input in `input`, with the default parameters
+/// Returns the answer, or NULL on failure with the reason available from `aoc_last_error`
+///
+/// # Safety
+/// `input` must point to `len` readable bytes
+#[no_mangle]
+pub unsafe extern "C" fn aoc_solve(day: u8, part: u8, input: *const u8, len: usize) -> *mut c_char {
+    aoc_solve_params(day, part, input, len, ptr::null())
+}
+
+/// Like `aoc_solve`, with the parameters of the day in `params`, one `name=value` per line,
+/// e.g. "width=11\nheight=7" for the sample of day 14
+///
+/// # Safety
+/// `input` must point to `len` readable bytes, `params` must be NULL or a NUL terminated string
+#[no_mangle]
+pub unsafe extern "C" fn aoc_solve_params(day: u8, part: u8, input: *const u8, len: usize, params: *const c_char) -> *mut c_char {
+    // A success must not leave the reason of an earlier failure behind
+    LAST_ERROR.with(|last| *last.borrow_mut() = None);
+
+    let input = if input.is_null() { &[][..] } else { slice::from_raw_parts(input, len) };
+    let params = if params.is_null() { Ok("") } else { CStr::from_ptr(params).to_str() };
+    let result = params.map_err(|e| format!("The parameters are not UTF-8: {e}")).and_then(|params| solve(day, part, input, params));
+    match result {
+        Ok(answer) => to_c_string(answer),
+        Err(e) => {
+            LAST_ERROR.with(|last| *last.borrow_mut() = Some(e));
+            ptr::null_mut()
+        },
+    }
+}
+
+/// The reason of the last failure on the calling thread, NULL if there was none
+#[no_mangle]
+pub extern "C" fn aoc_last_error() -> *mut c_char {
+    match LAST_ERROR.with(|last| last.borrow().clone()) {
+        Some(e) => to_c_string(e),
+        None => ptr::null_mut(),
+    }
+}
+
+/// Releases a string returned by the library, NULL is ignored
+///
+/// # Safety
+/// `s` must come from this library and must not be used afterwards
+#[no_mangle]
+pub unsafe extern "C" fn aoc_free_string(s: *mut c_char) {
+    if !s.is_null() { drop(CString::from_raw(s)); }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn take(s: *mut c_char) -> Option<String> {
+        if s.is_null() { return None }
+        let result = unsafe { CStr::from_ptr(s) }.to_string_lossy().to_string();
+        unsafe { aoc_free_string(s) };
+        Some(result)
+    }
+
+    #[test]
+    fn test_solve() {
+        let input = b"3   4\n4   3\n2   5\n1   3\n3   9\n3   3\n";
+        assert_eq!(take(unsafe { aoc_solve(1, 1, input.as_ptr(), input.len()) }), Some("11".to_string()));
+        assert_eq!(take(unsafe { aoc_solve(1, 2, input.as_ptr(), input.len()) }), Some("31".to_string()));
+
+        assert_eq!(take(unsafe { aoc_solve(1, 3, input.as_ptr(), input.len()) }), None);
+        assert_eq!(take(aoc_last_error()), Some("Day 1 has no part 3".to_string()));
+        assert_eq!(take(unsafe { aoc_solve(1, 1, input.as_ptr(), input.len()) }), Some("11".to_string()));
+        assert_eq!(take(aoc_last_error()), None);
+    }
+
+    #[test]
+    fn test_params() {
+        let input = b"p=0,4 v=3,-3\np=6,3 v=-1,-3\np=10,3 v=-1,2\np=2,0 v=2,-1\np=0,0 v=1,3\np=3,0 v=-2,-2\n\
+            p=7,6 v=-1,-3\np=3,0 v=-1,-2\np=9,3 v=2,3\np=7,3 v=-1,2\np=2,4 v=2,-3\np=9,5 v=-3,-3\n";
+        let params = c"width=11\nheight=7\n";
+        assert_eq!(take(unsafe { aoc_solve_params(14, 1, input.as_ptr(), input.len(), params.as_ptr()) }), Some("12".to_string()));
+
+        assert_eq!(take(unsafe { aoc_solve_params(14, 1, input.as_ptr(), input.len(), c"width".as_ptr()) }), None);
+        assert_eq!(take(aoc_last_error()), Some("Invalid parameter: width".to_string()));
+    }
+
+    #[test]
+    fn test_header() {
+        // The header in the source tree is not written by the build, it has to be copied when the API changes
+        let generated = include_str!(concat!(env!("OUT_DIR"), "/aoc2024.h"));
+        assert_eq!(include_str!("../include/aoc2024.h"), generated, "include/aoc2024.h is out of date");
+    }
+
+    #[test]
+    fn test_days() {
+        let count = unsafe { aoc_days(ptr::null_mut(), 0) };
+        let mut days = vec![0; count];
+        assert_eq!(unsafe { aoc_days(days.as_mut_ptr(), days.len()) }, count);
+        assert_eq!(days[0], 1);
+        assert_eq!(aoc_parts(5), 2);
+        assert_eq!(aoc_parts(25), 1);
+    }
+}
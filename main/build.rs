@@ -0,0 +1,39 @@
+// With the "embed-inputs" feature, generates the list of files to compile into the binary:
+// every dayN/*.txt of the workspace, their encrypted versions dayN/*.txt.enc and the default config
+
+use std::{env, fs, path::{Path, PathBuf}};
+
+fn main() {
+    println!("cargo:rerun-if-changed=build.rs");
+    if env::var_os("CARGO_FEATURE_EMBED_INPUTS").is_none() { return }
+
+    let root = PathBuf::from(env::var("CARGO_MANIFEST_DIR").unwrap()).join("..");
+    let mut files: Vec<(String, PathBuf)> = vec![];
+
+    for day in 1..=25 {
+        let dir = root.join(format!("day{day}"));
+        println!("cargo:rerun-if-changed={}", dir.display());
+        let Ok(entries) = fs::read_dir(&dir) else { continue };
+        for entry in entries.flatten() {
+            let path = entry.path();
+            let name = path.file_name().unwrap().to_string_lossy().to_string();
+            if name.ends_with(".txt") || name.ends_with(".txt.enc") {
+                files.push((format!("day{day}/{name}"), path));
+            }
+        }
+    }
+
+    let config = root.join("aoc2024.toml");
+    println!("cargo:rerun-if-changed={}", config.display());
+    if config.exists() { files.push(("aoc2024.toml".to_string(), config)); }
+
+    files.sort();
+    let entries = files
+        .iter()
+        .map(|(name, path)| format!("    ({name:?}, include_str!({:?})),\n", path.canonicalize().unwrap()))
+        .collect::<String>();
+    let code = format!("pub static FILES: &[(&str, &str)] = &[\n{entries}];\n");
+
+    let out = Path::new(&env::var("OUT_DIR").unwrap()).join("embedded.rs");
+    fs::write(out, code).unwrap();
+}
@@ -0,0 +1,60 @@
+// Running one day over every input file of a directory
+// The expected answers for an input are read from a file next to it with the same stem and the extension
+// "answers", one answer per line in part order, e.g. alice.txt and alice.answers
+// The process exits with code 2 if any answer differs from the expected one, is missing or a task failed
+
+use std::{collections::HashSet, fs, path::{Path, PathBuf}};
+
+use crate::report::{Status, TaskReport};
+
+const ANSWERS_EXTENSION: &str = "answers";
+
+/// All input files of the directory, sorted by name, without the answer files and hidden files
+pub fn inputs(dir: &Path) -> Result<Vec<PathBuf>, String> {
+    let entries = fs::read_dir(dir).map_err(|e| format!("Could not read {}: {e}", dir.display()))?;
+
+    let mut inputs = vec![];
+    for entry in entries {
+        let path = entry.map_err(|e| format!("Could not read {}: {e}", dir.display()))?.path();
+        if !path.is_file() { continue }
+        if path.extension().is_some_and(|ext| ext == ANSWERS_EXTENSION) { continue }
+        if path.file_name().is_some_and(|name| name.to_string_lossy().starts_with('.')) { continue }
+        inputs.push(path);
+    }
+    inputs.sort();
+
+    if inputs.is_empty() { return Err(format!("No input files in {}", dir.display())) }
+    Ok(inputs)
+}
+
+/// The expected answers stored next to an input, empty if there are none
+pub fn expected_answers(input: &Path) -> Result<Vec<String>, String> {
+    let path = input.with_extension(ANSWERS_EXTENSION);
+    if !path.exists() { return Ok(vec![]) }
+
+    let content = fs::read_to_string(&path).map_err(|e| format!("Could not read {}: {e}", path.display()))?;
+    Ok(content.lines().map(|line| line.trim().to_string()).collect())
+}
+
+pub fn print_summary(reports: &[TaskReport]) {
+    println!("Batch summary");
+    for report in reports.iter() {
+        let answers = report.answers
+            .iter()
+            .map(|a| match &a.expected {
+                Some(e) if *e != a.value => format!("{} (expected {e})", a.value),
+                _ => a.value.clone(),
+            })
+            .chain(report.missing.iter().map(|(part, e)| format!("part {part} missing (expected {e})")))
+            .collect::<Vec<String>>()
+            .join(", ");
+        let status = report.status();
+        println!("{} {}: {answers} [{}] {:.3?}", report.input, report.task, status.text(), report.elapsed());
+    }
+
+    let inputs = reports.iter().map(|r| &r.input).collect::<HashSet<_>>().len();
+    let mismatches = reports.iter().filter(|r| r.status() == Status::Wrong).count();
+    let missing = reports.iter().filter(|r| r.status() == Status::Missing).count();
+    let failed = reports.iter().filter(|r| r.status() == Status::Failed).count();
+    println!("{inputs} inputs, {mismatches} mismatches, {missing} missing answers, {failed} failed");
+}
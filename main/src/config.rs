@@ -0,0 +1,89 @@
+// Puzzle parameters per day, read from a TOML file
+// A section named after an input file overrides the parameters of the day for that input, e.g.
+//
+// [day14]
+// width = 101
+// height = 103
+//
+// [day14."data_sample.txt"]
+// width = 11
+// height = 7
+//
+// The special key "input" sets the file a day reads when none is given on the command line
+// and "answers" in an input section lists the expected answers per part, e.g.
+//
+// [day14."data_sample.txt"]
+// answers = [12]
+
+use std::{fs, path::Path};
+
+use toml::{Table, Value};
+
+pub const DEFAULT_FILENAME: &str = "aoc2024.toml";
+
+#[derive(Default)]
+pub struct Config {
+    table: Table,
+}
+
+fn value_to_string(value: &Value) -> Option<String> {
+    match value {
+        Value::String(s) => Some(s.clone()),
+        Value::Integer(i) => Some(i.to_string()),
+        Value::Float(f) => Some(f.to_string()),
+        Value::Boolean(b) => Some(b.to_string()),
+        _ => None,
+    }
+}
+
+impl Config {
+    pub fn load(path: &Path) -> Result<Config, String> {
+        let content = match fs::read_to_string(path) {
+            Ok(content) => content,
+            Err(e) => utils::embedded::get(path)
+                .map(|content| content.to_string())
+                .ok_or(format!("Could not read {}: {e}", path.display()))?,
+        };
+        let table = content.parse::<Table>().map_err(|e| format!("Invalid config {}: {e}", path.display()))?;
+        Ok(Config { table })
+    }
+
+    fn day(&self, day: u8) -> Option<&Table> {
+        self.table.get(&format!("day{day}")).and_then(|v| v.as_table())
+    }
+
+    /// The input file a day reads by default
+    pub fn input(&self, day: u8) -> Option<String> {
+        self.day(day)?.get("input").and_then(value_to_string)
+    }
+
+    /// The expected answers of a day for the given input file, in part order
+    pub fn answers(&self, day: u8, input: &str) -> Vec<String> {
+        let answers = self.day(day)
+            .and_then(|table| table.get(input))
+            .and_then(|input_table| input_table.get("answers"))
+            .and_then(|answers| answers.as_array());
+        match answers {
+            Some(answers) => answers.iter().filter_map(value_to_string).collect(),
+            None => vec![],
+        }
+    }
+
+    /// Parameters of a day for the given input file, the input specific ones win
+    pub fn params(&self, day: u8, input: &str) -> Vec<(String, String)> {
+        let mut params = vec![];
+        let Some(table) = self.day(day) else { return params };
+
+        for (key, value) in table.iter() {
+            if key == "input" { continue }
+            if let Some(value) = value_to_string(value) { params.push((key.clone(), value)); }
+        }
+        if let Some(Value::Table(input_table)) = table.get(input) {
+            for (key, value) in input_table.iter() {
+                if let Some(value) = value_to_string(value) { params.push((key.clone(), value)); }
+            }
+        }
+
+        params
+    }
+}
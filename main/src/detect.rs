@@ -0,0 +1,211 @@
+// Guessing the day of a puzzle input
+// Every day gets a score for how well the shape of the input fits it, e.g. two integer columns for day 1,
+// the days with a score are then checked by parsing the input with the parser of the day
+
+use std::{
+    panic::{self, AssertUnwindSafe},
+    path::Path,
+};
+
+use utils::Context;
+
+pub struct Candidate {
+    pub day: u8,
+    // Between 0 and 1, days fitting equally well share it
+    pub confidence: f64,
+}
+
+fn is_uint(s: &str) -> bool {
+    !s.is_empty() && s.chars().all(|c| c.is_ascii_digit())
+}
+
+fn is_int(s: &str) -> bool {
+    is_uint(s.strip_prefix('-').unwrap_or(s))
+}
+
+// Fraction of the non-empty lines matching
+fn share(lines: &[String], matches: impl Fn(&str) -> bool) -> f64 {
+    let lines = lines.iter().filter(|line| !line.is_empty()).collect::<Vec<_>>();
+    if lines.is_empty() { return 0.0 }
+    lines.iter().filter(|line| matches(line)).count() as f64 / lines.len() as f64
+}
+
+// Non-empty groups of lines separated by blank lines
+fn sections(lines: &[String]) -> Vec<&[String]> {
+    lines.split(|line| line.is_empty()).filter(|section| !section.is_empty()).collect()
+}
+
+// At least two rows of the same length with only the allowed characters
+fn is_grid(lines: &[String], allowed: impl Fn(char) -> bool) -> bool {
+    lines.len() >= 2
+        && lines.iter().all(|line| !line.is_empty() && line.len() == lines[0].len())
+        && lines.iter().all(|line| line.chars().all(&allowed))
+}
+
+fn count(lines: &[String], c: char) -> usize {
+    lines.iter().map(|line| line.matches(c).count()).sum()
+}
+
+fn to_score(fits: bool) -> f64 {
+    if fits { 1.0 } else { 0.0 }
+}
+
+// Open cells with more than two open neighbours, a race track (day 20) has none
+fn junctions(lines: &[String]) -> usize {
+    let grid = lines.iter().map(|line| line.as_bytes()).collect::<Vec<_>>();
+    let open = |x: usize, y: usize| grid.get(x).and_then(|row| row.get(y)).is_some_and(|c| *c != b'#');
+    let mut junctions = 0;
+    for (x, row) in grid.iter().enumerate() {
+        for y in 0..row.len() {
+            if !open(x, y) { continue }
+            let neighbours = [open(x + 1, y), open(x, y + 1), x > 0 && open(x - 1, y), y > 0 && open(x, y - 1)];
+            if neighbours.iter().filter(|n| **n).count() > 2 { junctions += 1; }
+        }
+    }
+    junctions
+}
+
+fn shape(day: u8, lines: &[String]) -> f64 {
+    let sections = sections(lines);
+    match day {
+        1 => {
+            let score = share(lines, |line| {
+                let columns = line.split_whitespace().collect::<Vec<_>>();
+                columns.len() == 2 && columns.iter().all(|c| is_int(c))
+            });
+            // A single line of two numbers is more likely day 11
+            if lines.len() < 2 { score / 4.0 } else { score }
+        },
+        2 => {
+            let score = share(lines, |line| line.split(' ').count() >= 2 && line.split(' ').all(is_int));
+            // A single line of numbers is more likely day 11
+            if lines.len() < 2 { score / 4.0 } else { score }
+        },
+        3 => to_score(lines.iter().any(|line| line.contains("mul("))),
+        4 => to_score(is_grid(lines, |c| "XMAS".contains(c))),
+        5 => {
+            let [rules, pages] = sections[..] else { return 0.0 };
+            let rule = |line: &str| line.split_once('|').is_some_and(|(a, b)| is_uint(a) && is_uint(b));
+            let update = |line: &str| line.split(',').all(is_uint);
+            (share(rules, rule) + share(pages, update)) / 2.0
+        },
+        6 => to_score(is_grid(lines, |c| ".#^".contains(c)) && count(lines, '^') == 1),
+        7 => share(lines, |line| {
+            line.split_once(": ").is_some_and(|(total, numbers)| is_uint(total) && numbers.split(' ').all(is_uint))
+        }),
+        8 => {
+            // Mostly empty with a few antennas
+            let dots = count(lines, '.');
+            let cells = lines.iter().map(|line| line.len()).sum::<usize>();
+            to_score(is_grid(lines, |c| c == '.' || c.is_ascii_alphanumeric()) && dots * 2 > cells && dots < cells)
+        },
+        9 => to_score(lines.len() == 1 && is_uint(&lines[0])),
+        10 => to_score(is_grid(lines, |c| c.is_ascii_digit())),
+        11 => match lines {
+            // A single number is more likely day 9
+            [line] if is_uint(line) => 0.5,
+            [line] => to_score(line.split(' ').all(is_uint)),
+            _ => 0.0,
+        },
+        12 => match is_grid(lines, |c| c.is_ascii_uppercase()) {
+            // XMAS letters only is more likely day 4
+            true if lines.iter().all(|line| line.chars().all(|c| "XMAS".contains(c))) => 0.5,
+            fits => to_score(fits),
+        },
+        13 => share(lines, |line| ["Button A: X+", "Button B: X+", "Prize: X="].iter().any(|p| line.starts_with(p))),
+        14 => share(lines, |line| line.starts_with("p=") && line.contains(" v=")),
+        15 => {
+            let [warehouse, moves] = sections[..] else { return 0.0 };
+            let fits = is_grid(warehouse, |c| "#.O@".contains(c)) && count(warehouse, '@') == 1;
+            if fits { share(moves, |line| line.chars().all(|c| "<>^v".contains(c))) } else { 0.0 }
+        },
+        16 | 20 => {
+            if !(is_grid(lines, |c| "#.SE".contains(c)) && count(lines, 'S') == 1 && count(lines, 'E') == 1) { return 0.0 }
+            // A maze with junctions is day 16, a single track is day 20
+            match (day, junctions(lines) > 0) {
+                (16, true) | (20, false) => 1.0,
+                _ => 0.3,
+            }
+        },
+        17 => {
+            if !lines.first().is_some_and(|line| line.starts_with("Register A: ")) { return 0.0 }
+            share(lines, |line| line.starts_with("Register ") || line.starts_with("Program: "))
+        },
+        18 => share(lines, |line| line.split_once(',').is_some_and(|(x, y)| is_uint(x) && is_uint(y))),
+        19 => {
+            let [towels, designs] = sections[..] else { return 0.0 };
+            let colours = |s: &str| !s.is_empty() && s.chars().all(|c| "wubrg".contains(c));
+            if towels.len() != 1 || !towels[0].split(", ").all(colours) { return 0.0 }
+            share(designs, colours)
+        },
+        21 => share(lines, |line| line.strip_suffix('A').is_some_and(|digits| digits.len() == 3 && is_uint(digits))),
+        22 => {
+            if lines.len() < 2 { return 0.0 }
+            // Numbers of the same length are more likely a grid of digits (day 10)
+            let same_length = lines.iter().all(|line| line.len() == lines[0].len());
+            share(lines, is_uint) * if same_length { 0.5 } else { 1.0 }
+        },
+        23 => share(lines, |line| {
+            line.split_once('-').is_some_and(|(a, b)| [a, b].iter().all(|c| c.len() == 2 && c.chars().all(|c| c.is_ascii_lowercase())))
+        }),
+        24 => share(lines, |line| {
+            let wire = line.split_once(": ").is_some_and(|(_, value)| value == "0" || value == "1");
+            let gate = line.split(' ').collect::<Vec<_>>();
+            let gate = gate.len() == 5 && ["AND", "OR", "XOR"].contains(&gate[1]) && gate[3] == "->";
+            wire || gate
+        }),
+        25 => {
+            if sections.is_empty() { return 0.0 }
+            let schematics = sections.iter().filter(|s| s.len() == 7 && is_grid(s, |c| ".#".contains(c)) && s[0].len() == 5);
+            schematics.count() as f64 / sections.len() as f64
+        },
+        _ => 0.0,
+    }
+}
+
+// None if `f` panics, without printing the panic message
+pub fn catch_silently<R>(f: impl FnOnce() -> R) -> Option<R> {
+    let hook = panic::take_hook();
+    panic::set_hook(Box::new(|_| {}));
+    let result = panic::catch_unwind(AssertUnwindSafe(f));
+    panic::set_hook(hook);
+    result.ok()
+}
+
+/// Whether the parser of the day accepts the input, parsers panic on invalid input
+pub fn parses(day: &solvers::Day, ctx: &Context) -> bool {
+    catch_silently(|| (day.parse)(ctx)).is_some()
+}
+
+/// The days the input could be for, the most likely first
+pub fn detect(input: &Path) -> Result<Vec<Candidate>, String> {
+    if !utils::embedded::exists(input) { return Err(format!("No such file: {}", input.display())) }
+    let ctx = Context::new(input);
+    let lines = ctx.lines().map(|line| line.trim_end().to_string()).collect::<Vec<String>>();
+
+    let mut scores = vec![];
+    for day in solvers::days().iter() {
+        let score = shape(day.number, &lines);
+        if score > 0.0 && parses(day, &ctx) { scores.push((day.number, score)); }
+    }
+
+    let total = scores.iter().map(|(_, score)| score).sum::<f64>().max(1.0);
+    let mut candidates = scores
+        .into_iter()
+        .map(|(day, score)| Candidate { day, confidence: score / total })
+        .collect::<Vec<Candidate>>();
+    candidates.sort_by(|a, b| b.confidence.total_cmp(&a.confidence).then(a.day.cmp(&b.day)));
+
+    Ok(candidates)
+}
+
+pub fn print_candidates(input: &Path, candidates: &[Candidate]) {
+    if candidates.is_empty() {
+        println!("{}: no day fits", input.display());
+        return
+    }
+    println!("{}: most likely day {}", input.display(), candidates[0].day);
+    for candidate in candidates.iter() {
+        println!("Day {}: {:.0}%", candidate.day, candidate.confidence * 100.0);
+    }
+}
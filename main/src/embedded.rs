@@ -0,0 +1,7 @@
+// Inputs compiled into the binary with the "embed-inputs" feature, see build.rs
+
+include!(concat!(env!("OUT_DIR"), "/embedded.rs"));
+
+pub fn register() {
+    utils::embedded::register(FILES);
+}
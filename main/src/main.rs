@@ -1,101 +1,306 @@
-use day1;
-use day2;
-use day3;
-use day4;
-use day5;
-use day6;
-use day7;
-use day8;
-use day9;
-use day10;
-use day11;
-use day12;
-use day13;
-use day14;
-use day15;
-use day16;
-use day17;
-use day18;
-use day19;
-use day20;
-use day21;
-use day22;
-use day23;
-use day24;
+use std::{
+    env,
+    path::{Path, PathBuf},
+    process,
+    time::Duration,
+};
 
-fn main() {
-    println!("AOC2024");
+use utils::{Context, Output};
+
+mod batch;
+mod config;
+mod detect;
+#[cfg(feature = "embed-inputs")]
+mod embedded;
+mod memory;
+mod reduce;
+mod repl;
+mod report;
+mod vault;
+mod worker;
+
+use config::Config;
+use solvers::{Day, Task};
+use report::{Answer, Phase, Status, TaskReport};
+
+const USAGE: &str = "Usage:
+  main run <all | day...> [--input <file>] [options]
+  main batch <day> <dir> [options]
+  main detect <file>...
+  main repl <day> [--input <file>] [options]
+  main reduce <day> <file> [--part <n>] [--reference <command>] [--output <file>] [options]
+  main vault <add <file>... | rotate | list>
+Options: [--config <file>] [--set <name>=<value>]... [--time-budget <seconds>] [--report <file>]";
+
+struct Options {
+    // Days for run, day and directory for batch
+    args: Vec<String>,
+    // File name inside the day directory, or a path
+    input: Option<String>,
+    config: Config,
+    overrides: Vec<(String, String)>,
+    // Per task, the runner kills a task once it is used up and reports it as timed out
+    time_budget: Option<Duration>,
+    // HTML report written after the run
+    report: Option<PathBuf>,
+    // For reduce: the part whose answer is checked, the command printing the right answer and the file for the result
+    part: Option<u8>,
+    reference: Option<String>,
+    output: Option<PathBuf>,
+}
+
+fn parse_options(args: &[String]) -> Result<Options, String> {
+    let mut positional = vec![];
+    let mut input = None;
+    let mut config_path = None;
+    let mut overrides = vec![];
+    let mut time_budget = None;
+    let mut report = None;
+    let mut part = None;
+    let mut reference = None;
+    let mut output = None;
+
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--input" => input = Some(args.next().ok_or("--input needs a file")?.clone()),
+            "--config" => config_path = Some(PathBuf::from(args.next().ok_or("--config needs a file")?)),
+            "--report" => report = Some(PathBuf::from(args.next().ok_or("--report needs a file")?)),
+            "--reference" => reference = Some(args.next().ok_or("--reference needs a command")?.clone()),
+            "--output" => output = Some(PathBuf::from(args.next().ok_or("--output needs a file")?)),
+            "--part" => {
+                let value = args.next().ok_or("--part needs a number")?;
+                part = Some(value.parse::<u8>().map_err(|_| format!("Invalid part: {value}"))?);
+            },
+            "--set" => {
+                let param = args.next().ok_or("--set needs <name>=<value>")?;
+                let (name, value) = param.split_once('=').ok_or(format!("Invalid parameter: {param}"))?;
+                overrides.push((name.to_string(), value.to_string()));
+            },
+            "--time-budget" => {
+                let seconds = args.next().ok_or("--time-budget needs a number of seconds")?;
+                let seconds = seconds.parse::<f64>().ok().filter(|s| *s >= 0.0).ok_or(format!("Invalid time budget: {seconds}"))?;
+                time_budget = Some(Duration::from_secs_f64(seconds));
+            },
+            _ => positional.push(arg.clone()),
+        }
+    }
+
+    // Without --config the default file is used, but only if it exists
+    let config = match config_path {
+        Some(path) => Config::load(&path)?,
+        None if utils::embedded::exists(config::DEFAULT_FILENAME) => Config::load(Path::new(config::DEFAULT_FILENAME))?,
+        None => Config::default(),
+    };
+
+    Ok(Options { args: positional, input, config, overrides, time_budget, report, part, reference, output })
+}
+
+fn file_name(path: &Path) -> String {
+    path.file_name().map(|f| f.to_string_lossy().to_string()).unwrap_or_default()
+}
+
+// The input of a day for run
+fn resolve_input(day: &Day, options: &Options) -> PathBuf {
+    let name = options.input
+        .clone()
+        .or(options.config.input(day.number))
+        .unwrap_or("data.txt".to_string());
+    let in_day_dir = Path::new(&format!("day{}", day.number)).join(&name);
+    if utils::embedded::exists(&in_day_dir) { in_day_dir } else { PathBuf::from(&name) }
+}
+
+fn make_context(day: &Day, input: &Path, options: &Options) -> Context {
+    let mut ctx = Context::new(input);
+    for (param, value) in options.config.params(day.number, &file_name(input)).iter().chain(options.overrides.iter()) {
+        ctx.set_param(param, value);
+    }
 
-    //day1::main_day1_task1(); // 1938424
-    //day1::main_day1_task2(); // 22014209
+    ctx
+}
 
-    //day2::main_day2_task1(); // 559
-    //day2::main_day2_task2(); // 601
+// Expected answers are indexed by part number - 1
+// The task runs in a child process, see worker. When the time budget is used up the runner kills it and goes on,
+// so nothing of it is left running next to the following tasks.
+fn run_task(day: &Day, task: &Task, ctx: &Context, time_budget: Option<Duration>, expected: &[String]) -> TaskReport {
+    let label = format!("Day {} {}", day.number, task.name);
+    let (phases, output, timed_out, failed) = match worker::run(day, task, ctx, time_budget) {
+        worker::Outcome::Finished { phases, output, cancelled } => (phases, output, cancelled, false),
+        worker::Outcome::Killed => {
+            let phases = vec![Phase { name: "until timeout".to_string(), elapsed: time_budget.unwrap_or_default(), stats: None }];
+            (phases, Output::default(), true, false)
+        },
+        worker::Outcome::Failed(e) => {
+            println!("{label} failed: {e}");
+            (vec![], Output::default(), false, true)
+        },
+    };
 
-    //day3::main_day3_task1(); // 161_085_926
-    //day3::main_day3_task2(); // 82_045_421
+    let elapsed: Duration = phases.iter().map(|phase| phase.elapsed).sum();
+    match (timed_out, failed) {
+        (true, _) => println!("{label}: timed out after {elapsed:.3?}"),
+        (false, true) => (),
+        (false, false) => println!(
+            "{label}: {}",
+            phases.iter().map(|phase| phase.to_string()).collect::<Vec<String>>().join("; "),
+        ),
+    }
 
-    //day4::main_day4_task1(); // 2521
-    //day4::main_day4_task2(); // 1912
+    // A task which timed out or failed has no answers anyway
+    let missing = task.parts
+        .iter()
+        .filter(|part| !timed_out && !failed && !output.answers.iter().any(|(p, _)| p == *part))
+        .filter_map(|part| expected.get(*part as usize - 1).map(|e| (*part, e.clone())))
+        .collect();
+    let answers = output.answers
+        .into_iter()
+        .map(|(part, value)| Answer { part, value, expected: expected.get(part as usize - 1).cloned() })
+        .collect();
+    TaskReport {
+        day: day.number,
+        task: task.name,
+        input: file_name(ctx.input()),
+        answers,
+        missing,
+        timed_out,
+        failed,
+        phases,
+        grids: output.grids,
+    }
+}
 
-    //let wrong_pages = day5::main_day5_task1(); // 4959
-    //day5::main_day5_task2(&wrong_pages); // 4655
+fn run_day(day: &Day, ctx: &Context, options: &Options, expected: &[String]) -> Vec<TaskReport> {
+    day.tasks
+        .iter()
+        .map(|task| run_task(day, task, ctx, options.time_budget, expected))
+        .collect()
+}
 
-    //day6::main_day6_task1(); // 4819
-    //day6::main_day6_task2(); // 1796
+fn select_day(arg: &str) -> Result<Day, String> {
+    let number = arg.parse::<u8>().map_err(|_| format!("Invalid day: {arg}"))?;
+    match solvers::get_day(number) {
+        Some(day) => Ok(day),
+        None if (1..=25).contains(&number) => Err(format!("Day {number} is not enabled in this build (feature \"day{number}\")")),
+        None => Err(format!("Unknown day: {number}")),
+    }
+}
 
-    //day7::main_day7_task1(); // 3598800864292
-    //day7::main_day7_task2(); // 340362529351427
+fn select_days(args: &[String]) -> Result<Vec<Day>, String> {
+    if args.is_empty() { return Err(USAGE.to_string()) }
+    if args.iter().any(|arg| arg == "all") { return Ok(solvers::days()) }
 
-    //day8::main_day8_task1(); // 413
-    //day8::main_day8_task2(); // 1417
+    args.iter().map(|arg| select_day(arg)).collect()
+}
 
-    //day9::main_day9_task1(); // 6461289671426
-    //day9::main_day9_task2(); // 6488291456470
+fn run(options: &Options) -> Result<Vec<TaskReport>, String> {
+    let mut reports = vec![];
+    for day in select_days(&options.args)?.iter() {
+        let ctx = make_context(day, &resolve_input(day, options), options);
+        let expected = options.config.answers(day.number, &file_name(ctx.input()));
+        reports.extend(run_day(day, &ctx, options, &expected));
+    }
 
-    //day10::main_day10_task1(); // 811
-    //day10::main_day10_task2(); // 1794
-    
-    //day11::main_day11_task1(); // 186996
-    //day11::main_day11_task2(); // 221683913164898
-    
-    //day12::main_day12(); // 1344578, 814302
-    
-    //day13::main_day13_task1(); // 35255
-    //day13::main_day13_task2(); // 87582154060429
+    Ok(reports)
+}
 
-    //day14::main_day14_task1(); // 221655456
-    //day14::main_day14_task2(); // 7858
+fn batch(options: &Options) -> Result<Vec<TaskReport>, String> {
+    let [day, dir] = options.args.as_slice() else { return Err(USAGE.to_string()) };
+    if options.input.is_some() { return Err("--input can not be used with batch".to_string()) }
+    let day = select_day(day)?;
 
-    //day15::main_day15_task1(); // 1360570
-    //day15::main_day15_task2(); // 1381446
+    let mut reports = vec![];
+    for input in batch::inputs(Path::new(dir))?.iter() {
+        println!("Input {}", input.display());
+        let ctx = make_context(&day, input, options);
+        reports.extend(run_day(&day, &ctx, options, &batch::expected_answers(input)?));
+    }
+    batch::print_summary(&reports);
 
-    //day16::main_day16_task1(); // 95444
-    //day16::main_day16_task2(); // 513
+    Ok(reports)
+}
 
-    //day17::main_day17_task1(); // 1,3,7,4,6,4,2,3,5
-    //day17::main_day17_task2(); // 202367025818154
+fn detect(options: &Options) -> Result<Vec<TaskReport>, String> {
+    if options.args.is_empty() { return Err(USAGE.to_string()) }
 
-    //day18::main_day18_task1(); // 314
-    //day18::main_day18_task2(); // 15,20
+    for input in options.args.iter() {
+        let input = Path::new(input);
+        detect::print_candidates(input, &detect::detect(input)?);
+    }
 
-    //day19::main_day19_task1(); // 213
-    //day19::main_day19_task2(); // 1016700771200474
+    Ok(vec![])
+}
 
-    //day20::main_day20_task1(); // 1459
-    //day20::main_day20_task2(); // 1016066
+fn repl(options: &Options) -> Result<Vec<TaskReport>, String> {
+    let [day] = options.args.as_slice() else { return Err(USAGE.to_string()) };
+    let day = select_day(day)?;
+    let input = resolve_input(&day, options);
+    if !utils::embedded::exists(&input) { return Err(format!("No such file: {}", input.display())) }
 
-    //day21::main_day21_task1(); // 137870
-    //day21::main_day21_task2(); // 170279148659464
+    Ok(repl::repl(&day, &input, options))
+}
 
-    //day22::main_day22_task1(); // 13185239446
-    //day22::main_day22_task2(); // 1501
+fn reduce(options: &Options) -> Result<Vec<TaskReport>, String> {
+    let [day, input] = options.args.as_slice() else { return Err(USAGE.to_string()) };
+    let day = select_day(day)?;
+    let input = Path::new(input);
+    if !utils::embedded::exists(input) { return Err(format!("No such file: {}", input.display())) }
 
-    //day23::main_day23_task1(); // 1083
-    //day23::main_day23_task2(); // as,bu,cp,dj,ez,fd,hu,it,kj,nx,pp,xh,yu
+    let part = options.part.unwrap_or(1);
+    if day.task(part).is_none() { return Err(format!("Day {} has no part {part}", day.number)) }
+    let failure = match &options.reference {
+        Some(command) => reduce::Failure::Reference(command.clone()),
+        None => reduce::Failure::Panic,
+    };
+    let params = options.config.params(day.number, &file_name(input)).into_iter().chain(options.overrides.iter().cloned()).collect();
+    let reducer = reduce::Reducer { day: &day, part, failure, time_budget: options.time_budget, params };
+
+    let output = options.output.clone().unwrap_or(reduce::sample_path(&day));
+    reduce::reduce(&reducer, input, &output)?;
+    println!("Written to {}", output.display());
+
+    Ok(vec![])
+}
+
+fn main() {
+    #[cfg(feature = "embed-inputs")]
+    embedded::register();
+
+    let args: Vec<String> = env::args().skip(1).collect();
+    // A task started by run_task, its output belongs to the run of the parent
+    if args.first().is_some_and(|arg| arg == worker::COMMAND) {
+        if let Err(e) = worker::child(&args[1..]) {
+            eprintln!("{e}");
+            process::exit(1);
+        }
+        return
+    }
+
+    println!("AOC2024");
+    let result = match args.first().map(|s| s.as_str()) {
+        Some("run") => parse_options(&args[1..]).and_then(|options| Ok((run(&options)?, options))),
+        Some("batch") => parse_options(&args[1..]).and_then(|options| Ok((batch(&options)?, options))),
+        Some("detect") => parse_options(&args[1..]).and_then(|options| Ok((detect(&options)?, options))),
+        Some("repl") => parse_options(&args[1..]).and_then(|options| Ok((repl(&options)?, options))),
+        Some("vault") => parse_options(&args[1..]).and_then(|options| Ok((vault::run(&options.args).map(|_| vec![])?, options))),
+        Some("reduce") => parse_options(&args[1..]).and_then(|options| Ok((reduce(&options)?, options))),
+        _ => Err(USAGE.to_string()),
+    };
 
-    //day24::main_day24_task1(); // 42049478636360
-    //day24::main_day24_task2(); // cph,gws,hgj,nnt,npf,z13,z19,z33
+    let result = result.and_then(|(reports, options)| {
+        if let Some(path) = &options.report {
+            report::write_html(path, &reports)?;
+            println!("Report written to {}", path.display());
+        }
+        Ok(reports)
+    });
 
-    day25::main_day25_task1(); // 3291
+    match result {
+        Err(e) => {
+            eprintln!("{e}");
+            process::exit(1);
+        },
+        // For CI, a batch fails if an answer differs from the expected one or a task failed
+        Ok(reports) if args[0] == "batch" && reports.iter().any(|r| matches!(r.status(), Status::Wrong | Status::Missing | Status::Failed)) => process::exit(2),
+        Ok(_) => (),
+    }
 }
@@ -0,0 +1,29 @@
+// Allocation tracking for the runner
+// Only active when built with the "alloc-stats" feature, otherwise all measurements are None
+
+use utils::alloc::AllocStats;
+
+#[cfg(feature = "alloc-stats")]
+#[global_allocator]
+static ALLOC: utils::alloc::CountingAllocator = utils::alloc::CountingAllocator::new();
+
+#[cfg(feature = "alloc-stats")]
+pub fn start() -> Option<AllocStats> {
+    ALLOC.reset_peak();
+    Some(ALLOC.stats())
+}
+
+#[cfg(feature = "alloc-stats")]
+pub fn finish(start: Option<AllocStats>) -> Option<AllocStats> {
+    start.map(|start| ALLOC.stats().since(&start))
+}
+
+#[cfg(not(feature = "alloc-stats"))]
+pub fn start() -> Option<AllocStats> {
+    None
+}
+
+#[cfg(not(feature = "alloc-stats"))]
+pub fn finish(_start: Option<AllocStats>) -> Option<AllocStats> {
+    None
+}
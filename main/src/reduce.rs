@@ -0,0 +1,122 @@
+// Shrinking an input on which a solver fails down to a few lines, written out as a new sample file of the day
+// A candidate only counts as failing if the parser of the day still accepts it, so the sample stays a valid input
+// Without a reference a failure is a panic of the solver, with one it is an answer different from the one
+// printed by the reference command, which gets the candidate file as its last argument
+
+use std::{
+    env, fs,
+    path::{Path, PathBuf},
+    process::Command,
+    sync::atomic::{AtomicUsize, Ordering},
+    time::Duration,
+};
+
+use solvers::Day;
+use utils::Context;
+
+use crate::detect;
+
+pub enum Failure {
+    Panic,
+    Reference(String),
+}
+
+pub struct Reducer<'a> {
+    pub day: &'a Day,
+    pub part: u8,
+    pub failure: Failure,
+    pub time_budget: Option<Duration>,
+    pub params: Vec<(String, String)>,
+}
+
+// Every candidate gets its own name, solvers might keep state per input
+static NEXT_CANDIDATE: AtomicUsize = AtomicUsize::new(0);
+
+impl Reducer<'_> {
+    // The answer for the part, None if the solver panicked or gave none
+    fn solve(&self, ctx: &Context) -> Option<String> {
+        let task = self.day.task(self.part)?;
+        ctx.progress().start(&format!("Day {} {}", self.day.number, task.name), self.time_budget);
+        detect::catch_silently(|| (task.run)(ctx))?;
+        if ctx.progress().was_cancelled() { return None }
+        ctx.take_output().answers.into_iter().find(|(part, _)| *part == self.part).map(|(_, answer)| answer)
+    }
+
+    fn reference(&self, command: &str, content: &str) -> Result<Option<String>, String> {
+        let path = env::temp_dir().join(format!("aoc2024-reduce-{}.txt", std::process::id()));
+        fs::write(&path, content).map_err(|e| format!("Could not write {}: {e}", path.display()))?;
+        let mut words = command.split_whitespace();
+        let program = words.next().ok_or("The reference command is empty")?;
+        let output = Command::new(program)
+            .args(words)
+            .arg(&path)
+            .output()
+            .map_err(|e| format!("Could not run {command}: {e}"));
+        _ = fs::remove_file(&path);
+
+        let output = output?;
+        if !output.status.success() { return Ok(None) }
+        Ok(Some(String::from_utf8_lossy(&output.stdout).trim().to_string()))
+    }
+
+    pub fn fails(&self, lines: &[String]) -> Result<bool, String> {
+        let mut content = lines.join("\n");
+        content.push('\n');
+
+        let name = format!("<candidate {}>", NEXT_CANDIDATE.fetch_add(1, Ordering::Relaxed));
+        utils::embedded::add(&name, content.clone());
+        let mut ctx = Context::new(&name);
+        for (param, value) in self.params.iter() {
+            ctx.set_param(param, value);
+        }
+        let fails = if !detect::parses(self.day, &ctx) {
+            Ok(false)
+        } else {
+            match &self.failure {
+                Failure::Panic => Ok(self.solve(&ctx).is_none() && !ctx.progress().was_cancelled()),
+                Failure::Reference(command) => match self.solve(&ctx) {
+                    Some(answer) => self.reference(command, &content).map(|expected| expected.is_some_and(|e| e != answer)),
+                    None => Ok(false),
+                },
+            }
+        };
+        utils::embedded::remove(&name);
+        fails
+    }
+}
+
+// The first of data_sample.txt, data_sample2.txt, ... which does not exist yet
+pub fn sample_path(day: &Day) -> PathBuf {
+    let dir = PathBuf::from(format!("day{}", day.number));
+    (1..)
+        .map(|i| dir.join(if i == 1 { "data_sample.txt".to_string() } else { format!("data_sample{i}.txt") }))
+        .find(|path| !path.exists())
+        .unwrap()
+}
+
+/// Reduces the input and writes the result to `output`, returns the number of lines kept
+pub fn reduce(reducer: &Reducer, input: &Path, output: &Path) -> Result<usize, String> {
+    let lines = Context::new(input).lines().collect::<Vec<String>>();
+    if !reducer.fails(&lines)? { return Err(format!("Day {} part {} does not fail on {}", reducer.day.number, reducer.part, input.display())) }
+
+    // The predicate can not return an error to ddmin, the first one is kept and ends the search
+    let mut error = None;
+    let mut tries = 0;
+    let reduced = utils::reduce::ddmin(&lines, |candidate| {
+        if error.is_some() { return false }
+        tries += 1;
+        println!("Try {tries}: {} lines", candidate.len());
+        reducer.fails(candidate).unwrap_or_else(|e| {
+            error = Some(e);
+            false
+        })
+    });
+    if let Some(e) = error { return Err(e) }
+
+    let mut content = reduced.join("\n");
+    content.push('\n');
+    fs::write(output, content).map_err(|e| format!("Could not write {}: {e}", output.display()))?;
+    println!("Reduced {} lines to {} in {tries} tries", lines.len(), reduced.len());
+
+    Ok(reduced.len())
+}
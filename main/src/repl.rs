@@ -0,0 +1,122 @@
+// Interactive session on the input of one day
+// Besides running the tasks with changed parameters, some days have their own commands working on the parsed input,
+// e.g. evaluating a wire of day 24, see solvers::explorer
+
+use std::{
+    io::{self, BufRead, Write},
+    panic::{self, AssertUnwindSafe},
+    path::Path,
+};
+
+use solvers::Day;
+use utils::{explore::Explore, Context};
+
+use crate::{file_name, make_context, report::TaskReport, run_task, Options};
+
+const COMMANDS: &[(&str, &str)] = &[
+    ("run [part]", "run the tasks, or only the one answering the part"),
+    ("set <name>=<value>", "change a parameter for the following runs and commands"),
+    ("params", "the parameters changed in this session"),
+    ("help", "this list"),
+    ("quit", "end the session"),
+];
+
+struct Session<'a> {
+    day: &'a Day,
+    input: &'a Path,
+    options: &'a Options,
+    overrides: Vec<(String, String)>,
+    explorer: Option<Box<dyn Explore>>,
+    reports: Vec<TaskReport>,
+}
+
+impl Session<'_> {
+    fn context(&self) -> Context {
+        let mut ctx = make_context(self.day, self.input, self.options);
+        for (param, value) in self.overrides.iter() {
+            ctx.set_param(param, value);
+        }
+        ctx
+    }
+
+    // Parses the input again, e.g. after a parameter changed
+    fn load(&mut self) {
+        let ctx = self.context();
+        self.explorer = panic::catch_unwind(|| solvers::explorer(self.day.number, &ctx)).unwrap_or_else(|_| {
+            println!("Could not load {}, only the general commands are available", self.input.display());
+            None
+        });
+    }
+
+    fn help(&self) {
+        let day_commands = self.explorer.as_ref().map(|e| e.commands()).unwrap_or_default();
+        for (usage, description) in COMMANDS.iter().chain(day_commands.iter()) {
+            println!("  {usage:<32} {description}");
+        }
+    }
+
+    fn run(&mut self, args: &[&str]) -> Result<(), String> {
+        let tasks = match args {
+            [] => self.day.tasks.iter().collect::<Vec<_>>(),
+            [part] => {
+                let part = part.parse::<u8>().map_err(|_| format!("Invalid part: {part}"))?;
+                vec![self.day.task(part).ok_or(format!("Day {} has no part {part}", self.day.number))?]
+            },
+            _ => return Err("Usage: run [part]".to_string()),
+        };
+
+        let expected = self.options.config.answers(self.day.number, &file_name(self.input));
+        for task in tasks {
+            let ctx = self.context();
+            self.reports.push(run_task(self.day, task, &ctx, self.options.time_budget, &expected));
+        }
+        Ok(())
+    }
+
+    fn execute(&mut self, command: &str, args: &[&str]) -> Result<(), String> {
+        match command {
+            "help" => self.help(),
+            "run" => self.run(args)?,
+            "set" => {
+                let [param] = args else { return Err("Usage: set <name>=<value>".to_string()) };
+                let (name, value) = param.split_once('=').ok_or(format!("Invalid parameter: {param}"))?;
+                self.overrides.retain(|(n, _)| n != name);
+                self.overrides.push((name.to_string(), value.to_string()));
+                self.load();
+            },
+            "params" => {
+                for (name, value) in self.overrides.iter() {
+                    println!("  {name}={value}");
+                }
+            },
+            _ => {
+                let explorer = self.explorer.as_mut().ok_or(format!("Unknown command: {command}, see help"))?;
+                let output = panic::catch_unwind(AssertUnwindSafe(|| explorer.run(command, args)))
+                    .map_err(|_| format!("{command} failed"))??;
+                println!("{output}");
+            },
+        }
+        Ok(())
+    }
+}
+
+/// Reads commands from stdin until quit or the end of the input, returns the reports of all runs
+pub fn repl(day: &Day, input: &Path, options: &Options) -> Vec<TaskReport> {
+    let mut session = Session { day, input, options, overrides: vec![], explorer: None, reports: vec![] };
+    session.load();
+    println!("Day {} with {}, type help for the commands", day.number, input.display());
+
+    let mut lines = io::stdin().lock().lines();
+    loop {
+        print!("day{}> ", day.number);
+        _ = io::stdout().flush();
+        let Some(Ok(line)) = lines.next() else { break };
+
+        let words = line.split_whitespace().collect::<Vec<&str>>();
+        let Some((command, args)) = words.split_first() else { continue };
+        if ["quit", "exit"].contains(command) { break }
+        if let Err(e) = session.execute(command, args) { println!("{e}"); }
+    }
+
+    session.reports
+}
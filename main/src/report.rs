@@ -0,0 +1,230 @@
+// Self-contained HTML report of a run: answers checked against the stored ones, timings, memory
+// and the grids the tasks recorded, drawn as inline SVG
+
+use std::{collections::HashMap, fmt::{self, Write as _}, fs, path::Path, time::Duration};
+
+use utils::{alloc::{format_bytes, AllocStats}, Grid};
+
+// Largest picture side in pixels
+const MAX_PICTURE_SIZE: usize = 640;
+
+// Colours of the cells, characters not listed here get one from PALETTE
+const WALL_COLOUR: &str = "#404040";
+const EMPTY_COLOUR: &str = "#ffffff";
+const PALETTE: [&str; 8] = ["#e6194b", "#3cb44b", "#4363d8", "#f58231", "#911eb4", "#42d4f4", "#f032e6", "#bfef45"];
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum Status {
+    Correct,
+    Wrong,
+    Unchecked,
+    NoAnswer,
+    TimedOut,
+    // The task did not record an answer for a part which has an expected answer
+    Missing,
+    // The task panicked or could not be run
+    Failed,
+}
+
+impl Status {
+    pub fn text(&self) -> &'static str {
+        match self {
+            Status::Correct => "correct",
+            Status::Wrong => "wrong",
+            Status::Unchecked => "unchecked",
+            Status::NoAnswer => "no answer",
+            Status::TimedOut => "timed out",
+            Status::Missing => "missing answer",
+            Status::Failed => "failed",
+        }
+    }
+}
+
+pub struct Answer {
+    pub part: u8,
+    pub value: String,
+    pub expected: Option<String>,
+}
+
+/// Time and memory of one part of running a task: "parse" reads the input with the parser of the day,
+/// "solve" is the task working on the parsed input, which it takes from the context
+pub struct Phase {
+    pub name: String,
+    pub elapsed: Duration,
+    pub stats: Option<AllocStats>,
+}
+
+impl fmt::Display for Phase {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {:.3?}", self.name, self.elapsed)?;
+        match &self.stats {
+            Some(stats) => write!(
+                f, ", peak {}, {} allocations, {} allocated",
+                format_bytes(stats.peak), stats.allocations, format_bytes(stats.allocated),
+            ),
+            None => Ok(()),
+        }
+    }
+}
+
+pub struct TaskReport {
+    pub day: u8,
+    pub task: &'static str,
+    pub input: String,
+    pub answers: Vec<Answer>,
+    // Parts of the task with an expected answer but no recorded one, with the expected answer
+    pub missing: Vec<(u8, String)>,
+    pub timed_out: bool,
+    pub failed: bool,
+    pub phases: Vec<Phase>,
+    pub grids: Vec<Grid>,
+}
+
+impl TaskReport {
+    /// Time of all phases together
+    pub fn elapsed(&self) -> Duration {
+        self.phases.iter().map(|phase| phase.elapsed).sum()
+    }
+
+    pub fn status(&self) -> Status {
+        if self.timed_out { return Status::TimedOut }
+        if self.failed { return Status::Failed }
+        if !self.missing.is_empty() { return Status::Missing }
+        if self.answers.is_empty() { return Status::NoAnswer }
+        if self.answers.iter().any(|a| a.expected.as_ref().is_some_and(|e| *e != a.value)) { return Status::Wrong }
+        if self.answers.iter().any(|a| a.expected.is_none()) { return Status::Unchecked }
+        Status::Correct
+    }
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;")
+}
+
+fn colour(c: char, others: &mut HashMap<char, &'static str>) -> &'static str {
+    match c {
+        '#' => WALL_COLOUR,
+        '.' | ' ' => EMPTY_COLOUR,
+        _ => {
+            let next = PALETTE[others.len() % PALETTE.len()];
+            others.entry(c).or_insert(next)
+        },
+    }
+}
+
+// One rect per run of equal characters in a row keeps the pictures small
+fn grid_to_svg(grid: &Grid) -> String {
+    let height = grid.rows.len();
+    let width = grid.rows.iter().map(|row| row.chars().count()).max().unwrap_or(0);
+    let cell = (MAX_PICTURE_SIZE / width.max(height).max(1)).clamp(1, 16);
+    let mut others = HashMap::new();
+
+    let mut svg = String::new();
+    _ = write!(svg, r#"<svg xmlns="http://www.w3.org/2000/svg" width="{}" height="{}" shape-rendering="crispEdges">"#, width * cell, height * cell);
+    _ = write!(svg, r#"<rect width="100%" height="100%" fill="{EMPTY_COLOUR}"/>"#);
+    for (y, row) in grid.rows.iter().enumerate() {
+        let chars: Vec<char> = row.chars().collect();
+        let mut x = 0;
+        while x < chars.len() {
+            let run = chars[x..].iter().take_while(|c| **c == chars[x]).count();
+            let fill = colour(chars[x], &mut others);
+            if fill != EMPTY_COLOUR {
+                _ = write!(svg, r#"<rect x="{}" y="{}" width="{}" height="{cell}" fill="{fill}"/>"#, x * cell, y * cell, run * cell);
+            }
+            x += run;
+        }
+    }
+    svg.push_str("</svg>");
+
+    let mut legend: Vec<(char, &str)> = others.into_iter().collect();
+    legend.sort();
+    let legend = legend
+        .iter()
+        .map(|(c, fill)| format!(r#"<span class="key" style="background:{fill}"></span>{}"#, escape(&c.to_string())))
+        .collect::<Vec<String>>()
+        .join(" ");
+
+    format!("<figure>{svg}<figcaption>{} {legend}</figcaption></figure>", escape(&grid.title))
+}
+
+fn task_row(report: &TaskReport) -> String {
+    let status = report.status();
+    let answers = report.answers
+        .iter()
+        .map(|a| match &a.expected {
+            Some(e) if *e != a.value => format!("part {}: {} (expected {})", a.part, escape(&a.value), escape(e)),
+            _ => format!("part {}: {}", a.part, escape(&a.value)),
+        })
+        .chain(report.missing.iter().map(|(part, e)| format!("part {part}: - (expected {})", escape(e))))
+        .collect::<Vec<String>>()
+        .join("<br>");
+    let time = report.phases
+        .iter()
+        .map(|phase| format!("{} {:.3?}", phase.name, phase.elapsed))
+        .collect::<Vec<String>>()
+        .join("<br>");
+    let memory = report.phases
+        .iter()
+        .filter_map(|phase| phase.stats.as_ref().map(|stats| {
+            format!("{}: {} peak, {} allocations", phase.name, format_bytes(stats.peak), stats.allocations)
+        }))
+        .collect::<Vec<String>>()
+        .join("<br>");
+    let memory = if memory.is_empty() { "-".to_string() } else { memory };
+
+    format!(
+        r#"<tr><td>{}</td><td>{}</td><td>{}</td><td>{answers}</td><td class="{}">{}</td><td>{time}</td><td>{memory}</td></tr>"#,
+        report.day, report.task, escape(&report.input), status.text().replace(' ', "-"), status.text(),
+    )
+}
+
+pub fn write_html(path: &Path, reports: &[TaskReport]) -> Result<(), String> {
+    let total: Duration = reports.iter().map(|r| r.elapsed()).sum();
+    let count = |status| reports.iter().filter(|r| r.status() == status).count();
+
+    let mut html = String::new();
+    html.push_str(r#"<!DOCTYPE html>
+<html>
+<head>
+<meta charset="utf-8">
+<title>AOC2024 run report</title>
+<style>
+body { font-family: sans-serif; margin: 2em; }
+table { border-collapse: collapse; }
+th, td { border: 1px solid #ccc; padding: 4px 8px; text-align: left; vertical-align: top; }
+.correct { background: #c8f0c8; }
+.wrong, .timed-out, .missing-answer, .failed { background: #f8c8c8; }
+.unchecked, .no-answer { background: #f0f0c8; }
+figure { display: inline-block; margin: 1em; }
+.key { display: inline-block; width: 0.8em; height: 0.8em; margin-left: 0.5em; }
+</style>
+</head>
+<body>
+<h1>AOC2024 run report</h1>
+"#);
+    _ = writeln!(
+        html,
+        "<p>{} tasks in {:.3?}: {} correct, {} wrong, {} unchecked, {} without answer, {} timed out, {} with a missing answer, {} failed</p>",
+        reports.len(), total,
+        count(Status::Correct), count(Status::Wrong), count(Status::Unchecked), count(Status::NoAnswer), count(Status::TimedOut),
+        count(Status::Missing), count(Status::Failed),
+    );
+
+    html.push_str("<table>\n<tr><th>Day</th><th>Task</th><th>Input</th><th>Answers</th><th>Status</th><th>Time</th><th>Memory</th></tr>\n");
+    for report in reports.iter() {
+        html.push_str(&task_row(report));
+        html.push('\n');
+    }
+    html.push_str("</table>\n");
+
+    for report in reports.iter().filter(|r| !r.grids.is_empty()) {
+        _ = writeln!(html, "<h2>Day {} {}</h2>", report.day, report.task);
+        for grid in report.grids.iter() {
+            html.push_str(&grid_to_svg(grid));
+            html.push('\n');
+        }
+    }
+    html.push_str("</body>\n</html>\n");
+
+    fs::write(path, html).map_err(|e| format!("Could not write {}: {e}", path.display()))
+}
@@ -0,0 +1,96 @@
+// Managing the encrypted inputs of utils::vault
+//
+// main vault add <file>...  encrypts the files with the key of AOC2024_VAULT_KEY, e.g. day1/data.txt to day1/data.txt.enc
+// main vault rotate         encrypts every vault file again with the key of AOC2024_VAULT_NEW_KEY
+// main vault list           shows every vault file and whether the current key opens it
+//
+// The plain files stay where they are and are still read first, they should not be committed.
+
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use utils::vault::{self, Key};
+
+const NEW_KEY_VAR: &str = "AOC2024_VAULT_NEW_KEY";
+
+// The vault files of all day directories, sorted
+fn vault_files() -> Vec<PathBuf> {
+    let mut files = vec![];
+    for day in 1..=25 {
+        let Ok(entries) = fs::read_dir(format!("day{day}")) else { continue };
+        for entry in entries.flatten() {
+            let path = entry.path();
+            if path.extension().is_some_and(|ext| ext == vault::EXTENSION) { files.push(path); }
+        }
+    }
+    files.sort();
+    files
+}
+
+fn read(path: &Path) -> Result<String, String> {
+    fs::read_to_string(path).map_err(|e| format!("Could not read {}: {e}", path.display()))
+}
+
+fn write(path: &Path, content: &str) -> Result<(), String> {
+    fs::write(path, content).map_err(|e| format!("Could not write {}: {e}", path.display()))
+}
+
+fn add(files: &[String]) -> Result<(), String> {
+    if files.is_empty() { return Err("Usage: main vault add <file>...".to_string()) }
+    let key = Key::from_env()?;
+
+    for file in files.iter() {
+        let path = Path::new(file);
+        let vault_path = vault::vault_path(path);
+        write(&vault_path, &vault::encrypt(&key, &read(path)?))?;
+        println!("Encrypted {} to {}", path.display(), vault_path.display());
+    }
+    Ok(())
+}
+
+fn rotate() -> Result<(), String> {
+    let key = Key::from_env()?;
+    let new_key = Key::from_var(NEW_KEY_VAR)?;
+
+    // Everything is decrypted first, so a wrong key does not leave the vault with two keys
+    let files = vault_files();
+    let inputs = files
+        .iter()
+        .map(|path| vault::decrypt(&key, &read(path)?).map_err(|e| format!("{}: {e}", path.display())))
+        .collect::<Result<Vec<String>, String>>()?;
+    for (path, input) in files.iter().zip(inputs.iter()) {
+        write(path, &vault::encrypt(&new_key, input))?;
+    }
+    println!("Encrypted {} files with the new key, it is to be used as {} from now on", files.len(), vault::KEY_VAR);
+    Ok(())
+}
+
+fn list() -> Result<(), String> {
+    let key = Key::from_env();
+    let files = vault_files();
+    for path in files.iter() {
+        let status = match &key {
+            Ok(key) => match vault::decrypt(key, &read(path)?) {
+                Ok(input) => format!("{} lines", input.lines().count()),
+                Err(e) => e,
+            },
+            Err(_) => "no key".to_string(),
+        };
+        let plain = path.with_extension("");
+        let plain = if plain.exists() { ", the plain file is present" } else { "" };
+        println!("{}: {status}{plain}", path.display());
+    }
+    println!("{} files in the vault", files.len());
+    Ok(())
+}
+
+pub fn run(args: &[String]) -> Result<(), String> {
+    match args.split_first() {
+        Some((command, files)) if command == "add" => add(files),
+        Some((command, [])) if command == "rotate" => rotate(),
+        Some((command, [])) if command == "list" => list(),
+        _ => Err("Usage: main vault <add <file>... | rotate | list>".to_string()),
+    }
+}
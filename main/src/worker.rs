@@ -0,0 +1,196 @@
+// Running one task in a child process, so a task which overruns its time budget can be killed
+// and a panic only fails that task
+// The runner starts this binary again as "main task <day> <task index> <input> <result file> <budget> [<name>=<value>]...",
+// the budget is in seconds or "-". The child prints the usual output and writes its measurements and output to the result file:
+//   cancelled <0 | 1>
+//   phase <nanoseconds> <current/peak/allocations/allocated | -> <name>
+//   answer <part> <value>
+//   grid <number of rows> <title>, followed by the rows
+
+use std::{
+    env, fs,
+    path::{Path, PathBuf},
+    process::{Command, Stdio},
+    sync::atomic::{AtomicUsize, Ordering},
+    thread,
+    time::{Duration, Instant},
+};
+
+use solvers::{Day, Task};
+use utils::{alloc::AllocStats, Context, Grid, Output};
+
+use crate::{memory, report::Phase};
+
+pub const COMMAND: &str = "task";
+
+// Stack of the thread a task runs on, some solvers recurse deeply
+const TASK_STACK_SIZE: usize = 256 << 20;
+// Solvers which check for cancellation stop at the budget themselves, they get this long to report before they are killed
+const KILL_GRACE: Duration = Duration::from_millis(200);
+const POLL_INTERVAL: Duration = Duration::from_millis(1);
+
+static NEXT_RESULT: AtomicUsize = AtomicUsize::new(0);
+
+pub enum Outcome {
+    // Stopped by itself, `cancelled` if it noticed the time budget was used up
+    Finished { phases: Vec<Phase>, output: Output, cancelled: bool },
+    // Killed after the time budget
+    Killed,
+    Failed(String),
+}
+
+// Time and allocations of one phase
+fn measure(name: &str, f: impl FnOnce()) -> Phase {
+    let stats_start = memory::start();
+    let time_start = Instant::now();
+    f();
+    Phase { name: name.to_string(), elapsed: time_start.elapsed(), stats: memory::finish(stats_start) }
+}
+
+/// Runs the task in a child process and waits for it, at most until the time budget is used up
+pub fn run(day: &Day, task: &Task, ctx: &Context, time_budget: Option<Duration>) -> Outcome {
+    let index = day.tasks.iter().position(|t| t.name == task.name).unwrap();
+    let result = env::temp_dir().join(format!("aoc2024-{}-{}.txt", std::process::id(), NEXT_RESULT.fetch_add(1, Ordering::Relaxed)));
+    let budget = time_budget.map(|b| b.as_secs_f64().to_string()).unwrap_or("-".to_string());
+    let params = ctx.params().map(|(name, value)| format!("{name}={value}"));
+
+    let exe = match env::current_exe() {
+        Ok(exe) => exe,
+        Err(e) => return Outcome::Failed(format!("Could not find the runner: {e}")),
+    };
+    let child = Command::new(exe)
+        .arg(COMMAND)
+        .args([day.number.to_string(), index.to_string()])
+        .arg(ctx.input())
+        .arg(&result)
+        .arg(budget)
+        .args(params)
+        .stdin(Stdio::null())
+        .spawn();
+    let mut child = match child {
+        Ok(child) => child,
+        Err(e) => return Outcome::Failed(format!("Could not start the task: {e}")),
+    };
+
+    let deadline = time_budget.map(|budget| Instant::now() + budget + KILL_GRACE);
+    let status = loop {
+        match child.try_wait() {
+            Ok(Some(status)) => break status,
+            Ok(None) if deadline.is_some_and(|d| Instant::now() >= d) => {
+                _ = child.kill();
+                _ = child.wait();
+                _ = fs::remove_file(&result);
+                return Outcome::Killed
+            },
+            Ok(None) => thread::sleep(POLL_INTERVAL),
+            Err(e) => return Outcome::Failed(format!("Could not wait for the task: {e}")),
+        }
+    };
+
+    let content = fs::read_to_string(&result);
+    _ = fs::remove_file(&result);
+    // The child printed the panic message already
+    if !status.success() { return Outcome::Failed(format!("exited with {status}")) }
+    match content.map_err(|e| e.to_string()).and_then(|content| read_result(&content)) {
+        Ok((phases, output, cancelled)) => Outcome::Finished { phases, output, cancelled },
+        Err(e) => Outcome::Failed(format!("Invalid result file {}: {e}", result.display())),
+    }
+}
+
+fn write_result(path: &Path, phases: &[Phase], output: &Output, cancelled: bool) -> Result<(), String> {
+    let mut content = format!("cancelled {}\n", cancelled as u8);
+    for phase in phases {
+        let stats = match &phase.stats {
+            Some(s) => format!("{}/{}/{}/{}", s.current, s.peak, s.allocations, s.allocated),
+            None => "-".to_string(),
+        };
+        content += &format!("phase {} {stats} {}\n", phase.elapsed.as_nanos(), phase.name);
+    }
+    for (part, value) in output.answers.iter() {
+        content += &format!("answer {part} {value}\n");
+    }
+    for grid in output.grids.iter() {
+        content += &format!("grid {} {}\n", grid.rows.len(), grid.title);
+        for row in grid.rows.iter() {
+            content += &format!("{row}\n");
+        }
+    }
+    fs::write(path, content).map_err(|e| format!("Could not write {}: {e}", path.display()))
+}
+
+fn read_result(content: &str) -> Result<(Vec<Phase>, Output, bool), String> {
+    let mut phases = vec![];
+    let mut output = Output::default();
+    let mut cancelled = false;
+
+    let mut lines = content.lines();
+    while let Some(line) = lines.next() {
+        let (kind, rest) = line.split_once(' ').ok_or(format!("Invalid line: {line}"))?;
+        match kind {
+            "cancelled" => cancelled = rest == "1",
+            "phase" => {
+                let [nanos, stats, name] = rest.splitn(3, ' ').collect::<Vec<&str>>()[..] else { return Err(format!("Invalid phase: {rest}")) };
+                let nanos = nanos.parse::<u64>().map_err(|e| format!("Invalid phase time {nanos}: {e}"))?;
+                let stats = match stats {
+                    "-" => None,
+                    _ => {
+                        let values = stats.split('/').map(|v| v.parse::<usize>()).collect::<Result<Vec<usize>, _>>();
+                        let [current, peak, allocations, allocated] = values.map_err(|e| e.to_string())?[..] else {
+                            return Err(format!("Invalid phase stats: {stats}"))
+                        };
+                        Some(AllocStats { current, peak, allocations, allocated })
+                    },
+                };
+                phases.push(Phase { name: name.to_string(), elapsed: Duration::from_nanos(nanos), stats });
+            },
+            "answer" => {
+                let (part, value) = rest.split_once(' ').unwrap_or((rest, ""));
+                output.answers.push((part.parse::<u8>().map_err(|e| format!("Invalid part {part}: {e}"))?, value.to_string()));
+            },
+            "grid" => {
+                let (rows, title) = rest.split_once(' ').unwrap_or((rest, ""));
+                let rows = rows.parse::<usize>().map_err(|e| format!("Invalid grid size {rows}: {e}"))?;
+                let rows = (0..rows).map(|_| lines.next().map(|row| row.to_string())).collect::<Option<Vec<String>>>();
+                output.grids.push(Grid { title: title.to_string(), rows: rows.ok_or("The last grid is incomplete")? });
+            },
+            _ => return Err(format!("Invalid line: {line}")),
+        }
+    }
+
+    Ok((phases, output, cancelled))
+}
+
+/// The child side: runs the task given by the arguments after COMMAND and writes the result file
+pub fn child(args: &[String]) -> Result<(), String> {
+    let [day, index, input, result, budget, params @ ..] = args else { return Err(format!("Usage: main {COMMAND} <day> <task> <input> <result> <budget> [<name>=<value>]...")) };
+    let day = day.parse::<u8>().ok().and_then(solvers::get_day).ok_or(format!("Unknown day: {day}"))?;
+    let task = index.parse::<usize>().ok().and_then(|i| day.tasks.get(i)).ok_or(format!("Unknown task: {index}"))?;
+    let budget = match budget.as_str() {
+        "-" => None,
+        _ => Some(Duration::from_secs_f64(budget.parse::<f64>().map_err(|e| format!("Invalid time budget {budget}: {e}"))?)),
+    };
+
+    let mut ctx = Context::new(input);
+    for param in params {
+        let (name, value) = param.split_once('=').ok_or(format!("Invalid parameter: {param}"))?;
+        ctx.set_param(name, value);
+    }
+    ctx.progress().start(&format!("Day {} {}", day.number, task.name), budget);
+
+    let (parse, run, worker_ctx) = (day.parse, task.run, ctx.clone());
+    let phases = thread::Builder::new()
+        .name(format!("day{} {}", day.number, task.name))
+        .stack_size(TASK_STACK_SIZE)
+        .spawn(move || {
+            let parse = measure("parse", || parse(&worker_ctx));
+            let mut solve = measure("solve", || run(&worker_ctx));
+            // The task did not take the parsed input, so it read the input again itself
+            if worker_ctx.has_parsed() { solve.name = "parse again and solve".to_string(); }
+            vec![parse, solve]
+        })
+        .map_err(|e| format!("Could not start a thread for the task: {e}"))?
+        .join()
+        .map_err(|_| format!("Day {} {} failed", day.number, task.name))?;
+
+    write_result(&PathBuf::from(result), &phases, &ctx.take_output(), ctx.progress().was_cancelled())
+}
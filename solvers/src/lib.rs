@@ -0,0 +1,181 @@
+// The solvers of all days enabled as cargo features, shared by the runner and the C API
+
+use utils::{explore::Explore, Context};
+
+#[derive(Clone, Copy)]
+pub struct Task {
+    pub name: &'static str,
+    // The parts whose answers the task records
+    pub parts: &'static [u8],
+    pub run: fn(&Context),
+}
+
+#[derive(Clone, Copy)]
+pub struct Day {
+    pub number: u8,
+    // Reads the input like the tasks do without solving anything, panics if the input does not fit the day
+    pub parse: fn(&Context),
+    pub tasks: &'static [Task],
+}
+
+// Only the days enabled as cargo features are registered
+#[allow(unused_mut, clippy::vec_init_then_push)]
+pub fn days() -> Vec<Day> {
+    let mut days = vec![];
+
+    #[cfg(feature = "day1")]
+    days.push(Day { number: 1, parse: day1::parse_input, tasks: &[
+        Task { name: "task 1", parts: &[1], run: day1::main_day1_task1 },
+        Task { name: "task 2", parts: &[2], run: day1::main_day1_task2 },
+    ] });
+    #[cfg(feature = "day2")]
+    days.push(Day { number: 2, parse: day2::parse_input, tasks: &[
+        Task { name: "task 1", parts: &[1], run: day2::main_day2_task1 },
+        Task { name: "task 2", parts: &[2], run: day2::main_day2_task2 },
+    ] });
+    #[cfg(feature = "day3")]
+    days.push(Day { number: 3, parse: day3::parse_input, tasks: &[
+        Task { name: "task 1", parts: &[1], run: day3::main_day3_task1 },
+        Task { name: "task 2", parts: &[2], run: day3::main_day3_task2 },
+    ] });
+    #[cfg(feature = "day4")]
+    days.push(Day { number: 4, parse: day4::parse_input, tasks: &[
+        Task { name: "task 1", parts: &[1], run: day4::main_day4_task1 },
+        Task { name: "task 2", parts: &[2], run: day4::main_day4_task2 },
+    ] });
+    #[cfg(feature = "day5")]
+    days.push(Day { number: 5, parse: day5::parse_input, tasks: &[
+        // Task 2 works on the wrongly ordered pages found by task 1
+        Task { name: "task 1+2", parts: &[1, 2], run: |ctx| {
+            let (rules, wrong_pages) = day5::main_day5_task1(ctx);
+            day5::main_day5_task2(ctx, &rules, &wrong_pages);
+        } },
+    ] });
+    #[cfg(feature = "day6")]
+    days.push(Day { number: 6, parse: day6::parse_input, tasks: &[
+        Task { name: "task 1", parts: &[1], run: day6::main_day6_task1 },
+        Task { name: "task 2", parts: &[2], run: day6::main_day6_task2 },
+    ] });
+    #[cfg(feature = "day7")]
+    days.push(Day { number: 7, parse: day7::parse_input, tasks: &[
+        Task { name: "task 1", parts: &[1], run: day7::main_day7_task1 },
+        Task { name: "task 2", parts: &[2], run: day7::main_day7_task2 },
+    ] });
+    #[cfg(feature = "day8")]
+    days.push(Day { number: 8, parse: day8::parse_input, tasks: &[
+        Task { name: "task 1", parts: &[1], run: day8::main_day8_task1 },
+        Task { name: "task 2", parts: &[2], run: day8::main_day8_task2 },
+    ] });
+    #[cfg(feature = "day9")]
+    days.push(Day { number: 9, parse: day9::parse_input, tasks: &[
+        Task { name: "task 1", parts: &[1], run: day9::main_day9_task1 },
+        Task { name: "task 2", parts: &[2], run: day9::main_day9_task2 },
+    ] });
+    #[cfg(feature = "day10")]
+    days.push(Day { number: 10, parse: day10::parse_input, tasks: &[
+        Task { name: "task 1", parts: &[1], run: day10::main_day10_task1 },
+        Task { name: "task 2", parts: &[2], run: day10::main_day10_task2 },
+    ] });
+    #[cfg(feature = "day11")]
+    days.push(Day { number: 11, parse: day11::parse_input, tasks: &[
+        Task { name: "task 1", parts: &[1], run: day11::main_day11_task1 },
+        Task { name: "task 2", parts: &[2], run: day11::main_day11_task2 },
+    ] });
+    #[cfg(feature = "day12")]
+    days.push(Day { number: 12, parse: day12::parse_input, tasks: &[
+        Task { name: "task 1+2", parts: &[1, 2], run: day12::main_day12 },
+    ] });
+    #[cfg(feature = "day13")]
+    days.push(Day { number: 13, parse: day13::parse_input, tasks: &[
+        Task { name: "task 1", parts: &[1], run: day13::main_day13_task1 },
+        Task { name: "task 2", parts: &[2], run: day13::main_day13_task2 },
+    ] });
+    #[cfg(feature = "day14")]
+    days.push(Day { number: 14, parse: day14::parse_input, tasks: &[
+        Task { name: "task 1", parts: &[1], run: day14::main_day14_task1 },
+        Task { name: "task 2", parts: &[2], run: day14::main_day14_task2 },
+    ] });
+    #[cfg(feature = "day15")]
+    days.push(Day { number: 15, parse: day15::parse_input, tasks: &[
+        Task { name: "task 1", parts: &[1], run: day15::main_day15_task1 },
+        Task { name: "task 2", parts: &[2], run: day15::main_day15_task2 },
+    ] });
+    #[cfg(feature = "day16")]
+    days.push(Day { number: 16, parse: day16::parse_input, tasks: &[
+        Task { name: "task 1", parts: &[1], run: day16::main_day16_task1 },
+        Task { name: "task 2", parts: &[2], run: day16::main_day16_task2 },
+    ] });
+    #[cfg(feature = "day17")]
+    days.push(Day { number: 17, parse: day17::parse_input, tasks: &[
+        Task { name: "task 1", parts: &[1], run: day17::main_day17_task1 },
+        Task { name: "task 2", parts: &[2], run: day17::main_day17_task2 },
+    ] });
+    #[cfg(feature = "day18")]
+    days.push(Day { number: 18, parse: day18::parse_input, tasks: &[
+        Task { name: "task 1", parts: &[1], run: day18::main_day18_task1 },
+        Task { name: "task 2", parts: &[2], run: day18::main_day18_task2 },
+    ] });
+    #[cfg(feature = "day19")]
+    days.push(Day { number: 19, parse: day19::parse_input, tasks: &[
+        Task { name: "task 1", parts: &[1], run: day19::main_day19_task1 },
+        Task { name: "task 2", parts: &[2], run: day19::main_day19_task2 },
+    ] });
+    #[cfg(feature = "day20")]
+    days.push(Day { number: 20, parse: day20::parse_input, tasks: &[
+        Task { name: "task 1", parts: &[1], run: day20::main_day20_task1 },
+        Task { name: "task 2", parts: &[2], run: day20::main_day20_task2 },
+    ] });
+    #[cfg(feature = "day21")]
+    days.push(Day { number: 21, parse: day21::parse_input, tasks: &[
+        Task { name: "task 1", parts: &[1], run: day21::main_day21_task1 },
+        Task { name: "task 2", parts: &[2], run: day21::main_day21_task2 },
+    ] });
+    #[cfg(feature = "day22")]
+    days.push(Day { number: 22, parse: day22::parse_input, tasks: &[
+        Task { name: "task 1", parts: &[1], run: day22::main_day22_task1 },
+        Task { name: "task 2", parts: &[2], run: day22::main_day22_task2 },
+    ] });
+    #[cfg(feature = "day23")]
+    days.push(Day { number: 23, parse: day23::parse_input, tasks: &[
+        Task { name: "task 1", parts: &[1], run: day23::main_day23_task1 },
+        Task { name: "task 2", parts: &[2], run: day23::main_day23_task2 },
+    ] });
+    #[cfg(feature = "day24")]
+    days.push(Day { number: 24, parse: day24::parse_input, tasks: &[
+        Task { name: "task 1", parts: &[1], run: day24::main_day24_task1 },
+        Task { name: "task 2", parts: &[2], run: day24::main_day24_task2 },
+    ] });
+    #[cfg(feature = "day25")]
+    days.push(Day { number: 25, parse: day25::parse_input, tasks: &[
+        Task { name: "task 1", parts: &[1], run: day25::main_day25_task1 },
+    ] });
+
+    days
+}
+
+pub fn get_day(number: u8) -> Option<Day> {
+    days().into_iter().find(|day| day.number == number)
+}
+
+// Days with their own commands for the REPL, the input is parsed once here
+#[allow(unused_variables)]
+pub fn explorer(number: u8, ctx: &Context) -> Option<Box<dyn Explore>> {
+    match number {
+        #[cfg(feature = "day12")]
+        12 => Some(day12::explore(ctx)),
+        #[cfg(feature = "day17")]
+        17 => Some(day17::explore(ctx)),
+        #[cfg(feature = "day23")]
+        23 => Some(day23::explore(ctx)),
+        #[cfg(feature = "day24")]
+        24 => Some(day24::explore(ctx)),
+        _ => None,
+    }
+}
+
+impl Day {
+    /// The task that answers the given part
+    pub fn task(&self, part: u8) -> Option<&'static Task> {
+        self.tasks.iter().find(|task| task.parts.contains(&part))
+    }
+}
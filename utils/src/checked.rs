@@ -0,0 +1,107 @@
+// Arithmetic on values coming from the puzzle input
+// With the feature "checked-arithmetic" an overflow panics, naming the day and the input line the values came from,
+// without it these are the plain operators, which wrap in release builds
+
+use std::{
+    fmt,
+    ops::{Add, Mul},
+};
+
+/// Where the values of a computation came from, the line is 1-based
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Origin {
+    pub day: u8,
+    pub line: usize,
+}
+
+impl Origin {
+    pub fn new(day: u8, line: usize) -> Self {
+        Origin { day, line }
+    }
+}
+
+impl fmt::Display for Origin {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Day {}, input line {}", self.day, self.line)
+    }
+}
+
+pub trait Int: Copy + fmt::Display + Add<Output = Self> + Mul<Output = Self> {
+    const NAME: &'static str;
+    const ZERO: Self;
+
+    fn checked_add(self, other: Self) -> Option<Self>;
+    fn checked_mul(self, other: Self) -> Option<Self>;
+    fn checked_pow(self, exp: u32) -> Option<Self>;
+    fn pow(self, exp: u32) -> Self;
+}
+
+macro_rules! impl_int {
+    ($($t:ty),*) => {
+        $(
+            impl Int for $t {
+                const NAME: &'static str = stringify!($t);
+                const ZERO: Self = 0;
+
+                fn checked_add(self, other: Self) -> Option<Self> { <$t>::checked_add(self, other) }
+                fn checked_mul(self, other: Self) -> Option<Self> { <$t>::checked_mul(self, other) }
+                fn checked_pow(self, exp: u32) -> Option<Self> { <$t>::checked_pow(self, exp) }
+                fn pow(self, exp: u32) -> Self { <$t>::pow(self, exp) }
+            }
+        )*
+    };
+}
+
+impl_int!(u32, u64, usize, i32, i64, isize, u128, i128);
+
+#[cfg(feature = "checked-arithmetic")]
+fn overflow<T: Int>(origin: Origin, a: T, op: &str, b: impl fmt::Display) -> ! {
+    panic!("{origin}: {a} {op} {b} overflows {}", T::NAME)
+}
+
+pub fn add<T: Int>(a: T, b: T, origin: Origin) -> T {
+    #[cfg(feature = "checked-arithmetic")]
+    return a.checked_add(b).unwrap_or_else(|| overflow(origin, a, "+", b));
+    #[cfg(not(feature = "checked-arithmetic"))]
+    { _ = origin; a + b }
+}
+
+pub fn mul<T: Int>(a: T, b: T, origin: Origin) -> T {
+    #[cfg(feature = "checked-arithmetic")]
+    return a.checked_mul(b).unwrap_or_else(|| overflow(origin, a, "*", b));
+    #[cfg(not(feature = "checked-arithmetic"))]
+    { _ = origin; a * b }
+}
+
+pub fn pow<T: Int>(base: T, exp: u32, origin: Origin) -> T {
+    #[cfg(feature = "checked-arithmetic")]
+    return base.checked_pow(exp).unwrap_or_else(|| overflow(origin, base, "^", exp));
+    #[cfg(not(feature = "checked-arithmetic"))]
+    { _ = origin; base.pow(exp) }
+}
+
+/// The sum of all values, e.g. of the answers per input line
+pub fn sum<T: Int>(values: impl IntoIterator<Item = (T, Origin)>) -> T {
+    values.into_iter().fold(T::ZERO, |total, (value, origin)| add(total, value, origin))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_in_range() {
+        let origin = Origin::new(7, 1);
+        assert_eq!(add(2_u64, 3, origin), 5);
+        assert_eq!(mul(-4_i64, 3, origin), -12);
+        assert_eq!(pow(10_u64, 19, origin), 10_000_000_000_000_000_000);
+        assert_eq!(sum([(1_i128, origin), (2, Origin::new(7, 2))]), 3);
+    }
+
+    #[cfg(feature = "checked-arithmetic")]
+    #[test]
+    #[should_panic(expected = "Day 11, input line 1: 9223372036854775808 * 2024 overflows u64")]
+    fn test_overflow() {
+        mul(1_u64 << 63, 2024, Origin::new(11, 1));
+    }
+}
@@ -0,0 +1,123 @@
+use std::{
+    any::Any,
+    collections::HashMap,
+    fmt::{Debug, Display},
+    path::{Path, PathBuf},
+    str::FromStr,
+    sync::{Arc, Mutex},
+};
+
+use crate::{Grid, Output, Progress};
+
+/// Everything a task gets from the runner: which input to read, the puzzle parameters and the progress handle
+/// The task hands its answers and grid pictures back through it
+#[derive(Clone)]
+pub struct Context {
+    input: PathBuf,
+    params: HashMap<String, String>,
+    progress: Arc<Progress>,
+    output: Arc<Mutex<Output>>,
+    // The input as parsed by the parse phase of the runner, until the task takes it
+    parsed: Arc<Mutex<Option<Box<dyn Any + Send>>>>,
+}
+
+impl Context {
+    pub fn new(input: impl Into<PathBuf>) -> Self {
+        Context { input: input.into(), params: HashMap::new(), progress: Arc::new(Progress::new()), output: Arc::default(), parsed: Arc::default() }
+    }
+
+    pub fn input(&self) -> &Path {
+        &self.input
+    }
+
+    pub fn progress(&self) -> &Progress {
+        &self.progress
+    }
+
+    /// Record the answer of a part, the task still prints it itself
+    pub fn answer(&self, part: u8, value: impl Display) {
+        self.output.lock().unwrap().answers.push((part, value.to_string()));
+    }
+
+    /// Record a picture of a grid, one string per row
+    pub fn grid(&self, title: &str, rows: impl IntoIterator<Item = String>) {
+        let grid = Grid { title: title.to_string(), rows: rows.into_iter().collect() };
+        self.output.lock().unwrap().grids.push(grid);
+    }
+
+    /// Everything recorded since the last call
+    pub fn take_output(&self) -> Output {
+        std::mem::take(&mut *self.output.lock().unwrap())
+    }
+
+    /// Keep the parsed input for the task, which gets it from `parsed` instead of reading the input again
+    pub fn keep_parsed<T: Any + Send>(&self, value: T) {
+        *self.parsed.lock().unwrap() = Some(Box::new(value));
+    }
+
+    /// The input kept by `keep_parsed`, or parsed now with `parse` if none of this type was kept,
+    /// e.g. when the task runs without a parse phase
+    pub fn parsed<T: Any + Send>(&self, parse: impl FnOnce(&Context) -> T) -> T {
+        let kept = self.parsed.lock().unwrap().take();
+        match kept.map(|value| value.downcast::<T>()) {
+            Some(Ok(value)) => *value,
+            _ => parse(self),
+        }
+    }
+
+    /// Whether a kept input was not taken by the task yet
+    pub fn has_parsed(&self) -> bool {
+        self.parsed.lock().unwrap().is_some()
+    }
+
+    pub fn lines(&self) -> impl Iterator<Item = String> {
+        crate::file_to_iter(self.input.clone())
+    }
+
+    pub fn reader(&self) -> Box<dyn std::io::BufRead> {
+        crate::open(&self.input)
+    }
+
+    /// All configured parameters, in no particular order
+    pub fn params(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.params.iter().map(|(name, value)| (name.as_str(), value.as_str()))
+    }
+
+    pub fn set_param(&mut self, name: &str, value: &str) {
+        self.params.insert(name.to_string(), value.to_string());
+    }
+
+    /// The value of a puzzle parameter, or `default` if it was not configured
+    pub fn param<T>(&self, name: &str, default: T) -> T
+    where
+        T: FromStr,
+        T::Err: Debug,
+    {
+        match self.params.get(name) {
+            Some(value) => value
+                .parse::<T>()
+                .unwrap_or_else(|e| panic!("Invalid value {value:?} for parameter {name}: {e:?}")),
+            None => default,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parsed() {
+        let ctx = Context::new("input.txt");
+        ctx.keep_parsed(vec![1, 2, 3]);
+        assert!(ctx.has_parsed());
+        assert_eq!(ctx.parsed(|_| vec![0]), vec![1, 2, 3]);
+        assert!(!ctx.has_parsed());
+        // Taken once, then parsed again
+        assert_eq!(ctx.parsed(|_| vec![0]), vec![0]);
+
+        // A kept input of another type is not used
+        ctx.keep_parsed("text");
+        assert_eq!(ctx.parsed(|_| 7), 7);
+    }
+}
@@ -0,0 +1,58 @@
+// Files that are not on disk: compiled into the binary, e.g. the puzzle inputs of a single binary distribution,
+// or added at runtime, e.g. an input buffer handed over the C API
+// A file on disk with the same name wins
+
+use std::{
+    collections::HashMap,
+    path::{Component, Path},
+    sync::{Mutex, OnceLock},
+};
+
+static FILES: OnceLock<&'static [(&'static str, &'static str)]> = OnceLock::new();
+static RUNTIME_FILES: Mutex<Option<HashMap<String, String>>> = Mutex::new(None);
+
+/// Make the compiled in files available, the names are relative paths with '/' as separator, e.g. "day1/data.txt"
+/// Only the first call has an effect
+pub fn register(files: &'static [(&'static str, &'static str)]) {
+    _ = FILES.set(files);
+}
+
+/// Add a file until `remove` is called, it replaces an earlier one with the same name
+pub fn add(name: impl AsRef<Path>, content: String) {
+    let mut files = RUNTIME_FILES.lock().unwrap();
+    files.get_or_insert_with(HashMap::new).insert(normalise(name.as_ref()), content);
+}
+
+pub fn remove(name: impl AsRef<Path>) {
+    if let Some(files) = RUNTIME_FILES.lock().unwrap().as_mut() {
+        files.remove(&normalise(name.as_ref()));
+    }
+}
+
+fn normalise(path: &Path) -> String {
+    path.components()
+        .filter(|c| *c != Component::CurDir)
+        .map(|c| c.as_os_str().to_string_lossy())
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+/// The content of a compiled in file
+pub fn get(path: impl AsRef<Path>) -> Option<&'static str> {
+    let name = normalise(path.as_ref());
+    FILES.get()?.iter().find(|(n, _)| *n == name).map(|(_, content)| *content)
+}
+
+/// The content of a file added at runtime or compiled in
+pub fn read(path: impl AsRef<Path>) -> Option<String> {
+    let name = normalise(path.as_ref());
+    if let Some(content) = RUNTIME_FILES.lock().unwrap().as_ref().and_then(|files| files.get(&name)) {
+        return Some(content.clone())
+    }
+    get(path).map(|content| content.to_string())
+}
+
+/// True if the file is on disk, added at runtime, compiled in or kept encrypted in the vault
+pub fn exists(path: impl AsRef<Path>) -> bool {
+    path.as_ref().exists() || read(&path).is_some() || crate::vault::exists(path)
+}
@@ -0,0 +1,16 @@
+// Commands of a day for the REPL of main, working on the parsed input
+
+pub trait Explore {
+    /// Usage and description of every command
+    fn commands(&self) -> &'static [(&'static str, &'static str)];
+
+    /// Runs a command, the output is printed by the REPL
+    /// Err for an unknown command or invalid arguments
+    fn run(&mut self, command: &str, args: &[&str]) -> Result<String, String>;
+}
+
+/// The argument at `index` parsed, with an error naming the command otherwise
+pub fn arg<T: std::str::FromStr>(args: &[&str], index: usize, usage: &str) -> Result<T, String> {
+    let arg = args.get(index).ok_or(format!("Usage: {usage}"))?;
+    arg.parse::<T>().map_err(|_| format!("Invalid argument {arg:?}, usage: {usage}"))
+}
@@ -1,19 +1,47 @@
+pub mod alloc;
+pub mod checked;
+pub mod context;
+pub mod embedded;
+pub mod explore;
+pub mod memo;
+pub mod number_theory;
+pub mod output;
+pub mod parse;
+pub mod progress;
+pub mod random;
+pub mod reduce;
+pub mod simulation;
+pub mod vault;
+
+pub use context::Context;
+pub use output::{Grid, Output};
+pub use progress::Progress;
+
 use std::{
     fs::File,
-    io::{prelude::*, BufReader},
+    io::{prelude::*, BufReader, Cursor},
     path::Path,
 };
 
+/// The file as a reader, files on disk win over the embedded ones, encrypted files in the vault come last
+pub fn open(filename: impl AsRef<Path>) -> Box<dyn BufRead> {
+    match File::open(&filename) {
+        Ok(file) => Box::new(BufReader::new(file)),
+        Err(_) => {
+            let content = embedded::read(&filename).or_else(|| vault::read(&filename));
+            Box::new(Cursor::new(content.expect("no such file")))
+        },
+    }
+}
+
 pub fn file_to_string_vector(filename: impl AsRef<Path>) -> Vec<String> {
-    let file = File::open(filename).expect("no such file");
-    let buf = BufReader::new(file);
+    let buf = open(filename);
     buf.lines()
         .map(|l| l.expect("Could not parse line"))
         .collect()
 }
 
 pub fn file_to_iter(filename: impl AsRef<Path>) -> impl Iterator<Item = String> {
-    let file = File::open(filename).expect("no such file");
-    let buf = BufReader::new(file);
+    let buf = open(filename);
     buf.lines().map(|l| l.expect("Could not parse line"))
 }
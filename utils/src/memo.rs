@@ -0,0 +1,151 @@
+// Memo tables for recursive functions
+//
+// Lookups take a borrowed key, e.g. a &str for a Memo<String, _>, so a key is only copied when a new value is stored.
+// Keys built from several strings can be interned first to get a cheap Copy key, e.g. (Interner id, depth).
+
+use std::{
+    borrow::Borrow,
+    collections::HashMap,
+    fmt,
+    hash::Hash,
+};
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct MemoStats {
+    pub hits: u64,
+    pub misses: u64,
+    pub entries: usize,
+}
+
+impl fmt::Display for MemoStats {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} hits, {} misses, {} entries", self.hits, self.misses, self.entries)
+    }
+}
+
+pub struct Memo<K, V> {
+    map: HashMap<K, V>,
+    // Once reached, new values are computed but not stored
+    cap: Option<usize>,
+    hits: u64,
+    misses: u64,
+}
+
+impl<K: Hash + Eq, V: Clone> Memo<K, V> {
+    pub fn new() -> Self {
+        Memo { map: HashMap::new(), cap: None, hits: 0, misses: 0 }
+    }
+
+    /// A memo that stores at most `cap` values
+    pub fn with_cap(cap: usize) -> Self {
+        Memo { cap: Some(cap), ..Self::new() }
+    }
+
+    /// The stored value, counted as a hit or a miss
+    pub fn get<Q>(&mut self, key: &Q) -> Option<V>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        match self.map.get(key) {
+            Some(value) => {
+                self.hits += 1;
+                Some(value.clone())
+            },
+            None => {
+                self.misses += 1;
+                None
+            },
+        }
+    }
+
+    /// Store a value unless the memo is full
+    pub fn insert(&mut self, key: K, value: V) {
+        if self.cap.is_some_and(|cap| self.map.len() >= cap) && !self.map.contains_key(&key) { return }
+        self.map.insert(key, value);
+    }
+
+    /// The stored value, or the one computed by `f`, which gets the memo for its recursive calls
+    pub fn get_or_insert_with<Q>(&mut self, key: &Q, f: impl FnOnce(&mut Self) -> V) -> V
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ToOwned<Owned = K> + ?Sized,
+    {
+        if let Some(value) = self.get(key) { return value }
+
+        let value = f(self);
+        self.insert(key.to_owned(), value.clone());
+        value
+    }
+
+    pub fn stats(&self) -> MemoStats {
+        MemoStats { hits: self.hits, misses: self.misses, entries: self.map.len() }
+    }
+}
+
+impl<K: Hash + Eq, V: Clone> Default for Memo<K, V> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Hands out a small id per distinct string, the same string always gets the same id
+#[derive(Default)]
+pub struct Interner {
+    ids: HashMap<String, u32>,
+    names: Vec<String>,
+}
+
+impl Interner {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn intern(&mut self, name: &str) -> u32 {
+        if let Some(id) = self.ids.get(name) { return *id }
+
+        let id = self.names.len() as u32;
+        self.ids.insert(name.to_string(), id);
+        self.names.push(name.to_string());
+        id
+    }
+
+    pub fn resolve(&self, id: u32) -> &str {
+        &self.names[id as usize]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fibonacci(n: u64, memo: &mut Memo<u64, u64>) -> u64 {
+        if n < 2 { return n }
+        memo.get_or_insert_with(&n, |memo| fibonacci(n - 1, memo) + fibonacci(n - 2, memo))
+    }
+
+    #[test]
+    fn test_memo() {
+        let mut memo = Memo::new();
+        assert_eq!(fibonacci(90, &mut memo), 2880067194370816120);
+        assert_eq!(memo.stats(), MemoStats { hits: 87, misses: 89, entries: 89 });
+
+        let mut memo = Memo::with_cap(10);
+        assert_eq!(fibonacci(30, &mut memo), 832040);
+        assert_eq!(memo.stats().entries, 10);
+    }
+
+    #[test]
+    fn test_borrowed_keys() {
+        let mut memo: Memo<String, usize> = Memo::new();
+        assert_eq!(memo.get_or_insert_with("abc", |_| 3), 3);
+        assert_eq!(memo.get_or_insert_with("abc", |_| 0), 3);
+        assert_eq!(memo.stats(), MemoStats { hits: 1, misses: 1, entries: 1 });
+
+        let mut interner = Interner::new();
+        let a = interner.intern("a");
+        assert_eq!(interner.intern("b"), a + 1);
+        assert_eq!(interner.intern("a"), a);
+        assert_eq!(interner.resolve(a), "a");
+    }
+}
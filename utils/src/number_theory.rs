@@ -0,0 +1,143 @@
+// Integer helpers: gcd/lcm, extended Euclid, modular inverse, Chinese remainder theorem
+// and exact solving of 2x2 linear systems
+// Everything works on i128 so products of puzzle sized values do not overflow
+
+/// Greatest common divisor, never negative, gcd(0, 0) is 0
+pub fn gcd(a: i128, b: i128) -> i128 {
+    let (mut a, mut b) = (a.abs(), b.abs());
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+    a
+}
+
+/// Least common multiple, never negative, 0 if one of them is 0
+pub fn lcm(a: i128, b: i128) -> i128 {
+    if a == 0 || b == 0 { return 0 }
+    (a / gcd(a, b) * b).abs()
+}
+
+/// (g, x, y) with a * x + b * y = g = gcd(a, b)
+pub fn extended_gcd(a: i128, b: i128) -> (i128, i128, i128) {
+    let (mut old_r, mut r) = (a, b);
+    let (mut old_x, mut x) = (1, 0);
+    let (mut old_y, mut y) = (0, 1);
+    while r != 0 {
+        let q = old_r / r;
+        (old_r, r) = (r, old_r - q * r);
+        (old_x, x) = (x, old_x - q * x);
+        (old_y, y) = (y, old_y - q * y);
+    }
+    if old_r < 0 { (-old_r, -old_x, -old_y) } else { (old_r, old_x, old_y) }
+}
+
+/// x in 0..m with a * x = 1 (mod m), None if a and m are not coprime
+pub fn mod_inverse(a: i128, m: i128) -> Option<i128> {
+    let (g, x, _) = extended_gcd(a.rem_euclid(m), m);
+    if g != 1 { return None }
+    Some(x.rem_euclid(m))
+}
+
+/// The smallest x >= 0 with x = r (mod m) for every (r, m), together with the lcm of the moduli
+/// The moduli do not have to be coprime, None if the congruences contradict each other
+pub fn crt(congruences: &[(i128, i128)]) -> Option<(i128, i128)> {
+    let mut x = 0;
+    let mut modulus = 1;
+    for &(r, m) in congruences.iter() {
+        // Find k with x + k * modulus = r (mod m)
+        let (g, p, _) = extended_gcd(modulus, m);
+        let diff = r - x;
+        if diff % g != 0 { return None }
+        let step = m / g;
+        let k = (diff / g % step * p).rem_euclid(step);
+        x += k * modulus;
+        modulus *= step;
+        x = x.rem_euclid(modulus);
+    }
+    Some((x, modulus))
+}
+
+/// An exact fraction, always reduced and with a positive denominator
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Ratio {
+    pub num: i128,
+    pub den: i128,
+}
+
+impl Ratio {
+    pub fn new(num: i128, den: i128) -> Ratio {
+        assert!(den != 0, "Denominator is 0");
+        let g = gcd(num, den) * den.signum();
+        Ratio { num: num / g, den: den / g }
+    }
+
+    /// The value if it is a whole number
+    pub fn integer(&self) -> Option<i128> {
+        if self.den == 1 { Some(self.num) } else { None }
+    }
+}
+
+/// The solution of a[0][0] * x + a[0][1] * y = b[0] and a[1][0] * x + a[1][1] * y = b[1]
+/// None if the system has no unique solution
+pub fn solve_2x2(a: [[i128; 2]; 2], b: [i128; 2]) -> Option<[Ratio; 2]> {
+    let det = a[0][0] * a[1][1] - a[0][1] * a[1][0];
+    if det == 0 { return None }
+    Some([
+        Ratio::new(b[0] * a[1][1] - a[0][1] * b[1], det),
+        Ratio::new(a[0][0] * b[1] - b[0] * a[1][0], det),
+    ])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_gcd() {
+        assert_eq!(gcd(12, 18), 6);
+        assert_eq!(gcd(-12, 18), 6);
+        assert_eq!(gcd(0, 5), 5);
+        assert_eq!(gcd(0, 0), 0);
+        assert_eq!(lcm(4, 6), 12);
+        assert_eq!(lcm(-4, 6), 12);
+        assert_eq!(lcm(0, 6), 0);
+
+        for (a, b) in [(240, 46), (-7, 3), (0, 9), (101, 103)] {
+            let (g, x, y) = extended_gcd(a, b);
+            assert_eq!(g, gcd(a, b));
+            assert_eq!(a * x + b * y, g);
+        }
+    }
+
+    #[test]
+    fn test_mod_inverse() {
+        assert_eq!(mod_inverse(3, 11), Some(4));
+        assert_eq!(mod_inverse(-3, 11), Some(7));
+        assert_eq!(mod_inverse(101, 103), Some(51));
+        assert_eq!(mod_inverse(6, 9), None);
+    }
+
+    #[test]
+    fn test_crt() {
+        assert_eq!(crt(&[(2, 3), (3, 5), (2, 7)]), Some((23, 105)));
+        // Day 14: x repeats every 101 seconds, y every 103
+        assert_eq!(crt(&[(7858 % 101, 101), (7858 % 103, 103)]), Some((7858, 10403)));
+        // Moduli with a common factor
+        assert_eq!(crt(&[(2, 4), (4, 6)]), Some((10, 12)));
+        assert_eq!(crt(&[(1, 4), (2, 6)]), None);
+        assert_eq!(crt(&[]), Some((0, 1)));
+    }
+
+    #[test]
+    fn test_solve_2x2() {
+        // Day 13 sample, first machine: 80 presses of A and 40 of B
+        let [a, b] = solve_2x2([[94, 22], [34, 67]], [8400, 5400]).unwrap();
+        assert_eq!((a.integer(), b.integer()), (Some(80), Some(40)));
+
+        let [x, y] = solve_2x2([[2, 0], [0, -4]], [1, 2]).unwrap();
+        assert_eq!((x, y), (Ratio::new(1, 2), Ratio { num: -1, den: 2 }));
+        assert_eq!(x.integer(), None);
+
+        assert_eq!(solve_2x2([[1, 2], [2, 4]], [3, 6]), None);
+    }
+}
@@ -0,0 +1,14 @@
+/// A picture of a grid state as rows of characters, e.g. the final warehouse of day 15
+#[derive(Debug, Clone)]
+pub struct Grid {
+    pub title: String,
+    pub rows: Vec<String>,
+}
+
+/// What a task handed back to the runner besides printing it
+#[derive(Debug, Clone, Default)]
+pub struct Output {
+    /// Part number and answer
+    pub answers: Vec<(u8, String)>,
+    pub grids: Vec<Grid>,
+}
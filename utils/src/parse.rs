@@ -0,0 +1,534 @@
+// Helpers to describe an input format once and get typed values out of it
+// Every value is carried around as a `Field` which knows where it came from, so errors point at line and column
+
+use std::{fmt, ops::Deref, str::FromStr};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    pub line: usize,
+    pub column: usize,
+    pub message: String,
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}, column {}: {}", self.line, self.column, self.message)
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+pub type Result<T> = std::result::Result<T, ParseError>;
+
+/// A piece of the input together with its position (1-based line and column)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Field<'a> {
+    text: &'a str,
+    line: usize,
+    column: usize,
+}
+
+impl<'a> From<&'a str> for Field<'a> {
+    fn from(text: &'a str) -> Self {
+        Field::at(text, 1, 1)
+    }
+}
+
+impl Deref for Field<'_> {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        self.text
+    }
+}
+
+impl<'a> Field<'a> {
+    pub fn at(text: &'a str, line: usize, column: usize) -> Self {
+        Field { text, line, column }
+    }
+
+    pub fn as_str(&self) -> &'a str {
+        self.text
+    }
+
+    pub fn line(&self) -> usize {
+        self.line
+    }
+
+    pub fn column(&self) -> usize {
+        self.column
+    }
+
+    /// Error pointing at the start of this field
+    pub fn error(&self, message: impl Into<String>) -> ParseError {
+        ParseError { line: self.line, column: self.column, message: message.into() }
+    }
+
+    /// Sub-field for the byte range `start..end`, with the position adjusted
+    fn slice(&self, start: usize, end: usize) -> Field<'a> {
+        let before = &self.text[..start];
+        match before.rfind('\n') {
+            Some(i) => Field {
+                text: &self.text[start..end],
+                line: self.line + before.matches('\n').count(),
+                column: before[i + 1..].chars().count() + 1,
+            },
+            None => Field {
+                text: &self.text[start..end],
+                line: self.line,
+                column: self.column + before.chars().count(),
+            },
+        }
+    }
+
+    fn trim(&self) -> Field<'a> {
+        let start = self.text.len() - self.text.trim_start().len();
+        let end = self.text.trim_end().len().max(start);
+        self.slice(start, end)
+    }
+
+    /// The whole field (surrounding whitespace ignored) as a single value
+    pub fn parse<T>(&self) -> Result<T>
+    where
+        T: FromStr,
+        T::Err: fmt::Display,
+    {
+        let field = self.trim();
+        if field.text.is_empty() { return Err(field.error("expected a value, found nothing")) }
+        field.text
+            .parse::<T>()
+            .map_err(|e| field.error(format!("invalid value {:?}: {e}", field.text)))
+    }
+
+    /// All the integers in the field, ignoring whatever is around them
+    /// A '-' directly in front of the digits is taken as the sign
+    pub fn integers<T>(&self) -> Result<Vec<T>>
+    where
+        T: FromStr,
+        T::Err: fmt::Display,
+    {
+        let bytes = self.text.as_bytes();
+        let mut result = vec![];
+        let mut i = 0;
+
+        while i < bytes.len() {
+            if !bytes[i].is_ascii_digit() {
+                i += 1;
+                continue
+            }
+            let start = if i > 0 && bytes[i - 1] == b'-' { i - 1 } else { i };
+            while i < bytes.len() && bytes[i].is_ascii_digit() { i += 1; }
+            result.push(self.slice(start, i).parse()?);
+        }
+
+        Ok(result)
+    }
+
+    /// Split on `separator`; a whitespace separator splits on any run of whitespace
+    pub fn split(&self, separator: &str) -> Vec<Field<'a>> {
+        let mut fields = vec![];
+
+        if separator.trim().is_empty() {
+            let mut start = None;
+            for (i, c) in self.text.char_indices() {
+                match (c.is_whitespace(), start) {
+                    (true, Some(s)) => {
+                        fields.push(self.slice(s, i));
+                        start = None;
+                    },
+                    (false, None) => start = Some(i),
+                    _ => (),
+                }
+            }
+            if let Some(s) = start { fields.push(self.slice(s, self.text.len())); }
+        }
+        else {
+            let mut start = 0;
+            for (i, _) in self.text.match_indices(separator) {
+                fields.push(self.slice(start, i));
+                start = i + separator.len();
+            }
+            fields.push(self.slice(start, self.text.len()));
+        }
+
+        fields
+    }
+
+    /// A list of values separated by `separator`
+    pub fn list<T>(&self, separator: &str) -> Result<Vec<T>>
+    where
+        T: FromStr,
+        T::Err: fmt::Display,
+    {
+        self.split(separator).iter().map(|field| field.parse()).collect()
+    }
+
+    /// A "key<separator>value" record, e.g. "Register A: 729"
+    pub fn key_value(&self, separator: &str) -> Result<(Field<'a>, Field<'a>)> {
+        match self.text.find(separator) {
+            Some(i) => Ok((
+                self.slice(0, i).trim(),
+                self.slice(i + separator.len(), self.text.len()).trim(),
+            )),
+            None => Err(self.error(format!("expected {separator:?}"))),
+        }
+    }
+}
+
+/// Apply `f` to every line, with the line numbers filled in
+pub fn lines<I, S, T, F>(lines: I, mut f: F) -> Result<Vec<T>>
+where
+    I: IntoIterator<Item = S>,
+    S: AsRef<str>,
+    F: FnMut(Field) -> Result<T>,
+{
+    lines
+        .into_iter()
+        .enumerate()
+        .map(|(i, line)| f(Field::at(line.as_ref(), i + 1, 1)))
+        .collect()
+}
+
+/// A group of consecutive non-blank lines
+#[derive(Debug)]
+pub struct Block<'a, S> {
+    /// Line number of the first line in the block
+    pub line: usize,
+    pub lines: &'a [S],
+}
+
+impl<'a, S: AsRef<str>> Block<'a, S> {
+    /// Line number of the last line in the block
+    pub fn last_line(&self) -> usize {
+        self.line + self.lines.len() - 1
+    }
+
+    /// Error about the block as a whole, pointing at its first line and naming its line range
+    pub fn error(&self, message: impl fmt::Display) -> ParseError {
+        ParseError {
+            line: self.line,
+            column: 1,
+            message: format!("block of lines {}-{}: {message}", self.line, self.last_line()),
+        }
+    }
+
+    /// The `i`th line of the block, trailing whitespace ignored
+    pub fn field(&self, i: usize) -> Result<Field<'a>> {
+        match self.lines.get(i) {
+            Some(line) => Ok(Field::at(line.as_ref().trim_end(), self.line + i, 1)),
+            None => Err(self.error(format!("{} lines, expected at least {}", self.lines.len(), i + 1))),
+        }
+    }
+
+    /// All lines of the block, trailing whitespace ignored
+    pub fn fields(&self) -> impl Iterator<Item = Field<'a>> + '_ {
+        (0..self.lines.len()).map(|i| self.field(i).unwrap())
+    }
+
+    /// The first line, e.g. the one telling what kind of record the block is
+    pub fn header(&self) -> Field<'a> {
+        self.field(0).unwrap()
+    }
+
+    /// The lines after the header
+    pub fn body(&self) -> impl Iterator<Item = Field<'a>> + '_ {
+        self.fields().skip(1)
+    }
+
+    /// Error unless the block has exactly `count` lines
+    pub fn expect_lines(&self, count: usize) -> Result<()> {
+        if self.lines.len() == count { return Ok(()) }
+        Err(self.error(format!("{} lines, expected {count}", self.lines.len())))
+    }
+}
+
+/// Split lines into blocks separated by one or more blank lines
+/// Lines with only whitespace count as blank, leading and trailing blank lines are ignored
+pub fn blocks<S: AsRef<str>>(lines: &[S]) -> Vec<Block<'_, S>> {
+    let mut blocks = vec![];
+    let mut start = None;
+
+    for (i, line) in lines.iter().enumerate() {
+        match (line.as_ref().trim().is_empty(), start) {
+            (true, Some(s)) => {
+                blocks.push(Block { line: s + 1, lines: &lines[s..i] });
+                start = None;
+            },
+            (false, None) => start = Some(i),
+            _ => (),
+        }
+    }
+    if let Some(s) = start { blocks.push(Block { line: s + 1, lines: &lines[s..] }); }
+
+    blocks
+}
+
+/// Blocks with a fixed meaning given by their order, e.g. the warehouse and the moves of day 15
+#[derive(Debug)]
+pub struct Sections<'a, S> {
+    names: &'static [&'static str],
+    blocks: Vec<Block<'a, S>>,
+}
+
+impl<'a, S: AsRef<str>> Sections<'a, S> {
+    /// The section called `name`, panics for a name which was not given to `sections`
+    pub fn get(&self, name: &str) -> &Block<'a, S> {
+        let i = self.names.iter().position(|n| *n == name).unwrap_or_else(|| panic!("Unknown section {name:?}"));
+        &self.blocks[i]
+    }
+}
+
+impl<'a, S> std::ops::Index<usize> for Sections<'a, S> {
+    type Output = Block<'a, S>;
+
+    fn index(&self, i: usize) -> &Block<'a, S> {
+        &self.blocks[i]
+    }
+}
+
+/// Split lines into exactly one block per name, in the given order
+pub fn sections<'a, S: AsRef<str>>(lines: &'a [S], names: &'static [&'static str]) -> Result<Sections<'a, S>> {
+    let blocks = blocks(lines);
+    if let Some(extra) = blocks.get(names.len()) {
+        return Err(extra.error(format!("unexpected section, expected only {}", names.join(", "))))
+    }
+    if blocks.len() < names.len() {
+        return Err(ParseError {
+            line: lines.len() + 1,
+            column: 1,
+            message: format!("missing section {}, expected {}", names[blocks.len()], names.join(", ")),
+        })
+    }
+    Ok(Sections { names, blocks })
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Placeholder {
+    // {} - anything up to the following literal
+    Any,
+    // {int} - optionally signed integer
+    Int,
+    // {uint} - digits only
+    UInt,
+    // {word} - letters, digits and underscores
+    Word,
+}
+
+// Byte ranges of the placeholders and where the match ends
+type Match = (Vec<(usize, usize)>, usize);
+// Byte offset and reason of a mismatch
+type Mismatch = (usize, String);
+
+#[derive(Debug, Clone, PartialEq)]
+enum Part {
+    Literal(String),
+    Field(Placeholder),
+}
+
+/// A fixed line format, e.g. `Template::new("Button A: X+{}, Y+{}")`
+/// Placeholders are `{}`, `{int}`, `{uint}` and `{word}`; `{}` has to be followed by a literal or the end
+#[derive(Debug, Clone)]
+pub struct Template {
+    pattern: String,
+    parts: Vec<Part>,
+}
+
+impl Template {
+    pub fn new(pattern: &str) -> Self {
+        let mut parts = vec![];
+        let mut rest = pattern;
+
+        while let Some(start) = rest.find('{') {
+            let end = start + rest[start..].find('}').expect("Unclosed placeholder in template");
+            if start > 0 { parts.push(Part::Literal(rest[..start].to_string())); }
+            let placeholder = match &rest[start + 1..end] {
+                "" => Placeholder::Any,
+                "int" => Placeholder::Int,
+                "uint" => Placeholder::UInt,
+                "word" => Placeholder::Word,
+                p => panic!("Unknown placeholder {{{p}}} in template"),
+            };
+            if let Some(Part::Field(_)) = parts.last() {
+                if placeholder == Placeholder::Any {
+                    panic!("Placeholder {{}} can not directly follow another placeholder");
+                }
+            }
+            parts.push(Part::Field(placeholder));
+            rest = &rest[end + 1..];
+        }
+        if !rest.is_empty() { parts.push(Part::Literal(rest.to_string())); }
+
+        for pair in parts.windows(2) {
+            if let [Part::Field(Placeholder::Any), Part::Field(_)] = pair {
+                panic!("Placeholder {{}} has to be followed by a literal");
+            }
+        }
+
+        Template { pattern: pattern.to_string(), parts }
+    }
+
+    /// Try to match starting at byte `start`
+    fn match_at(&self, text: &str, start: usize) -> std::result::Result<Match, Mismatch> {
+        let bytes = text.as_bytes();
+        let mut ranges = vec![];
+        let mut pos = start;
+
+        for (i, part) in self.parts.iter().enumerate() {
+            match part {
+                Part::Literal(literal) => {
+                    if !text[pos..].starts_with(literal.as_str()) {
+                        return Err((pos, format!("expected {literal:?}")))
+                    }
+                    pos += literal.len();
+                },
+                Part::Field(Placeholder::Any) => {
+                    let end = match self.parts.get(i + 1) {
+                        Some(Part::Literal(next)) => match text[pos..].find(next.as_str()) {
+                            Some(j) => pos + j,
+                            None => return Err((pos, format!("expected {next:?}"))),
+                        },
+                        _ => text.len(),
+                    };
+                    if end == pos { return Err((pos, "expected a value, found nothing".to_string())) }
+                    ranges.push((pos, end));
+                    pos = end;
+                },
+                Part::Field(placeholder) => {
+                    let mut end = pos;
+                    if *placeholder == Placeholder::Int && end < bytes.len() && bytes[end] == b'-' { end += 1; }
+                    let digits_start = end;
+                    while end < bytes.len() && match placeholder {
+                        Placeholder::Word => bytes[end].is_ascii_alphanumeric() || bytes[end] == b'_',
+                        _ => bytes[end].is_ascii_digit(),
+                    } { end += 1; }
+                    if end == digits_start {
+                        let expected = match placeholder {
+                            Placeholder::Word => "a word",
+                            _ => "an integer",
+                        };
+                        return Err((pos, format!("expected {expected}")))
+                    }
+                    ranges.push((pos, end));
+                    pos = end;
+                },
+            }
+        }
+
+        Ok((ranges, pos))
+    }
+
+    /// The placeholders of a line which has to match the template completely
+    pub fn captures<'a>(&self, text: impl Into<Field<'a>>) -> Result<Vec<Field<'a>>> {
+        let field = text.into();
+        match self.match_at(field.text, 0) {
+            Ok((_, end)) if end < field.text.len() => Err(field.slice(end, end).error(
+                format!("unexpected {:?} after {:?}", &field.text[end..], self.pattern)
+            )),
+            Ok((ranges, _)) => Ok(ranges.into_iter().map(|(s, e)| field.slice(s, e)).collect()),
+            Err((offset, message)) => Err(field.slice(offset, offset).error(
+                format!("{message} (format is {:?})", self.pattern)
+            )),
+        }
+    }
+
+    /// The placeholders of a line which has to match the template completely, all parsed into `T`
+    pub fn parse<'a, T>(&self, text: impl Into<Field<'a>>) -> Result<Vec<T>>
+    where
+        T: FromStr,
+        T::Err: fmt::Display,
+    {
+        self.captures(text)?.iter().map(|field| field.parse()).collect()
+    }
+
+    /// All non-overlapping occurrences of the template anywhere in the text, noise in between is skipped
+    pub fn find_iter<'a>(&'a self, text: impl Into<Field<'a>>) -> impl Iterator<Item = Vec<Field<'a>>> + 'a {
+        let field = text.into();
+        let mut pos = 0;
+
+        std::iter::from_fn(move || {
+            while pos < field.text.len() {
+                let start = match self.parts.first() {
+                    Some(Part::Literal(literal)) => pos + field.text[pos..].find(literal.as_str())?,
+                    _ => pos,
+                };
+                match self.match_at(field.text, start) {
+                    Ok((ranges, end)) if end > start => {
+                        pos = end;
+                        return Some(ranges.into_iter().map(|(s, e)| field.slice(s, e)).collect())
+                    },
+                    _ => {
+                        pos = start + field.text[start..].chars().next().map_or(1, |c| c.len_utf8());
+                    },
+                }
+            }
+            None
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_template() {
+        let button = Template::new("Button A: X+{}, Y+{}");
+        assert_eq!(button.parse::<i64>("Button A: X+94, Y+34").unwrap(), vec![94, 34]);
+
+        let e = button.parse::<i64>(Field::at("Button A: X+94, Y+3x", 7, 1)).unwrap_err();
+        assert_eq!((e.line, e.column), (7, 19));
+        let e = button.parse::<i64>("Button B: X+94, Y+34").unwrap_err();
+        assert_eq!((e.line, e.column), (1, 1));
+    }
+
+    #[test]
+    fn test_find_iter() {
+        let mul = Template::new("mul({uint},{uint})");
+        let found: Vec<Vec<u64>> = mul
+            .find_iter("xmul(2,4)%&mul[3,7]!@^do_not_mul(5,5)+mul(32,64]then(mul(11,8)mul(8,5))")
+            .map(|caps| caps.iter().map(|c| c.parse().unwrap()).collect())
+            .collect();
+        assert_eq!(found, vec![vec![2, 4], vec![5, 5], vec![11, 8], vec![8, 5]]);
+    }
+
+    #[test]
+    fn test_integers_and_records() {
+        assert_eq!(Field::from("p=0,4 v=3,-3").integers::<i64>().unwrap(), vec![0, 4, 3, -3]);
+
+        let (key, value) = Field::from("Register A: 729").key_value(":").unwrap();
+        assert_eq!((key.as_str(), value.parse::<u64>().unwrap()), ("Register A", 729));
+
+        let e = Field::from("Program: 0,1,x").key_value(":").unwrap().1.list::<u64>(",").unwrap_err();
+        assert_eq!((e.line, e.column), (1, 14));
+
+        let lines = ["a", "b", "", "", "c"];
+        let blocks = blocks(&lines);
+        assert_eq!(blocks.len(), 2);
+        assert_eq!(blocks[1].field(0).unwrap().line(), 5);
+        assert!(blocks[1].field(1).is_err());
+    }
+
+    #[test]
+    fn test_blocks_and_sections() {
+        // No trailing blank line, whitespace-only separators and trailing whitespace
+        let lines = ["", "r, wr, b  ", "   ", "brwrr", "bggr", "\t"];
+        let blocks = blocks(&lines);
+        assert_eq!(blocks.len(), 2);
+        assert_eq!(blocks[0].header().as_str(), "r, wr, b");
+        assert_eq!((blocks[1].line, blocks[1].last_line()), (4, 5));
+        assert_eq!(blocks[1].body().map(|f| f.line()).collect::<Vec<_>>(), vec![5]);
+
+        let e = blocks[1].expect_lines(3).unwrap_err();
+        assert_eq!(e.to_string(), "line 4, column 1: block of lines 4-5: 2 lines, expected 3");
+        assert!(blocks[1].expect_lines(2).is_ok());
+
+        let sections = sections(&lines, &["towels", "designs"]).unwrap();
+        assert_eq!(sections.get("designs").line, 4);
+        assert_eq!(sections[0].line, 2);
+
+        let e = super::sections(&lines, &["towels"]).unwrap_err();
+        assert_eq!(e.to_string(), "line 4, column 1: block of lines 4-5: unexpected section, expected only towels");
+        let e = super::sections(&lines[..3], &["towels", "designs"]).unwrap_err();
+        assert_eq!(e.to_string(), "line 4, column 1: missing section designs, expected towels, designs");
+    }
+}
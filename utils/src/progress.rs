@@ -0,0 +1,117 @@
+use std::{
+    sync::{
+        atomic::{AtomicBool, AtomicU64, Ordering},
+        Mutex,
+    },
+    time::{Duration, Instant},
+};
+
+// Minimum time between two progress lines
+const REPORT_INTERVAL: Duration = Duration::from_secs(1);
+// Stored as the deadline when there is no time budget
+const NO_DEADLINE: u64 = u64::MAX;
+
+struct State {
+    label: String,
+    start: Instant,
+    last_report: Instant,
+}
+
+/// Handle a long running task uses to report how far it got and to find out whether it should stop
+///
+/// Tasks call `is_cancelled` in their main loop and return early when it is true,
+/// which happens after `cancel` or once the time budget given to `start` is used up.
+pub struct Progress {
+    cancelled: AtomicBool,
+    // Nanoseconds from `origin` until the time budget is used up, atomic as tasks check it in their inner loops
+    deadline: AtomicU64,
+    origin: Instant,
+    state: Mutex<State>,
+}
+
+impl Progress {
+    pub fn new() -> Self {
+        let now = Instant::now();
+        Progress {
+            cancelled: AtomicBool::new(false),
+            deadline: AtomicU64::new(NO_DEADLINE),
+            origin: now,
+            state: Mutex::new(State { label: String::new(), start: now, last_report: now }),
+        }
+    }
+
+    /// Reset for the next task, optionally with a time budget
+    pub fn start(&self, label: &str, budget: Option<Duration>) {
+        let now = Instant::now();
+        let mut state = self.state.lock().unwrap();
+        *state = State { label: label.to_string(), start: now, last_report: now };
+        // A budget too large to ever run out counts as none
+        let deadline = budget.and_then(|b| (now - self.origin).checked_add(b)).and_then(|d| u64::try_from(d.as_nanos()).ok());
+        self.deadline.store(deadline.unwrap_or(NO_DEADLINE), Ordering::Relaxed);
+        self.cancelled.store(false, Ordering::Relaxed);
+    }
+
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+    }
+
+    /// True once cancelled or over the time budget
+    pub fn is_cancelled(&self) -> bool {
+        if self.cancelled.load(Ordering::Relaxed) { return true }
+
+        let deadline = self.deadline.load(Ordering::Relaxed);
+        if deadline != NO_DEADLINE && self.origin.elapsed().as_nanos() >= deadline as u128 {
+            self.cancel();
+            return true
+        }
+
+        false
+    }
+
+    /// True if the last task was stopped, without looking at the clock again
+    pub fn was_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Relaxed)
+    }
+
+    /// Print `done` (out of `total` if known) to stderr, at most once per second
+    pub fn report(&self, done: u64, total: Option<u64>) {
+        let now = Instant::now();
+        let mut state = self.state.lock().unwrap();
+        if now - state.last_report < REPORT_INTERVAL { return }
+        state.last_report = now;
+
+        let elapsed = (now - state.start).as_secs_f64();
+        match total {
+            Some(total) if total > 0 => eprintln!(
+                "{}: {done}/{total} ({:.1}%) after {elapsed:.1}s",
+                state.label, 100.0 * done as f64 / total as f64,
+            ),
+            _ => eprintln!("{}: {done} after {elapsed:.1}s", state.label),
+        }
+    }
+}
+
+impl Default for Progress {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_deadline() {
+        let progress = Progress::new();
+        progress.start("test", None);
+        assert!(!progress.is_cancelled());
+        progress.start("test", Some(Duration::from_secs(3600)));
+        assert!(!progress.is_cancelled());
+        progress.start("test", Some(Duration::ZERO));
+        assert!(progress.is_cancelled() && progress.was_cancelled());
+        // A new start forgets the old budget
+        progress.start("test", Some(Duration::MAX));
+        assert!(!progress.is_cancelled());
+    }
+}
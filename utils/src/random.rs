@@ -0,0 +1,38 @@
+// Reproducible pseudo random numbers for tests that compare a solver against brute force
+// Not for anything that needs good randomness, e.g. the vault takes its nonces from getrandom
+
+/// 64 bit linear congruential generator with the constants of Knuth's MMIX
+#[derive(Debug, Clone)]
+pub struct Lcg {
+    state: u64,
+}
+
+impl Lcg {
+    pub fn new(seed: u64) -> Self {
+        Lcg { state: seed }
+    }
+
+    /// The next state, the low bits are not very random
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+        self.state
+    }
+
+    /// A number in 0..n from the high bits of the next state
+    pub fn below(&mut self, n: u64) -> u64 {
+        (self.next_u64() >> 33) % n
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lcg() {
+        let numbers: Vec<u64> = (0..5).scan(Lcg::new(1), |rng, _| Some(rng.below(10))).collect();
+        assert_eq!(numbers, (0..5).scan(Lcg::new(1), |rng, _| Some(rng.below(10))).collect::<Vec<u64>>());
+        assert!(numbers.iter().all(|n| *n < 10));
+        assert_ne!(Lcg::new(1).next_u64(), Lcg::new(2).next_u64());
+    }
+}
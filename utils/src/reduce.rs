@@ -0,0 +1,60 @@
+// Delta debugging (ddmin): shrinking a failing input to a smaller one that still fails
+// The result is 1-minimal, removing any single item from it makes the failure go away
+
+/// The smallest part of `items` found for which `fails` still holds, items keep their order
+/// `fails` should hold for `items` itself, otherwise they are returned unchanged
+pub fn ddmin<T: Clone>(items: &[T], mut fails: impl FnMut(&[T]) -> bool) -> Vec<T> {
+    let mut items = items.to_vec();
+    let mut granularity = 2;
+
+    while items.len() >= 2 {
+        let chunk = items.len().div_ceil(granularity);
+        let chunks = items.chunks(chunk).map(|c| c.to_vec()).collect::<Vec<Vec<T>>>();
+
+        // One chunk alone is failing
+        if let Some(subset) = chunks.iter().find(|c| fails(c)) {
+            items = subset.clone();
+            granularity = 2;
+            continue
+        }
+
+        // Everything but one chunk is failing
+        let complement = (0..chunks.len())
+            .map(|skip| chunks
+                .iter()
+                .enumerate()
+                .filter(|(i, _)| *i != skip)
+                .flat_map(|(_, c)| c.iter().cloned())
+                .collect::<Vec<T>>()
+            )
+            .find(|complement| fails(complement));
+        if let Some(complement) = complement {
+            items = complement;
+            granularity = (granularity - 1).max(2);
+            continue
+        }
+
+        if granularity >= items.len() { break }
+        granularity = (granularity * 2).min(items.len());
+    }
+
+    items
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ddmin() {
+        let items = (0..100).collect::<Vec<u32>>();
+        assert_eq!(ddmin(&items, |c| c.contains(&42)), vec![42]);
+        assert_eq!(ddmin(&items, |c| c.contains(&7) && c.contains(&93)), vec![7, 93]);
+        // Fails once the sum is large enough, any single item is removable as long as it stays so
+        let reduced = ddmin(&items, |c| c.iter().sum::<u32>() >= 250);
+        assert!(reduced.iter().sum::<u32>() >= 250);
+        assert!(reduced.iter().all(|x| reduced.iter().sum::<u32>() - x < 250));
+
+        assert_eq!(ddmin(&items, |c| c.len() > 200), items);
+    }
+}
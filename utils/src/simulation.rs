@@ -0,0 +1,110 @@
+// Step by step simulations: a guard walking a maze, a robot pushing boxes, a small computer running a program
+
+use std::{
+    collections::{hash_map::Entry, HashMap},
+    hash::Hash,
+};
+
+/// How a run ended, all counts are steps from the start of the run
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Outcome {
+    /// The simulation was over, e.g. the guard left the maze
+    Finished(usize),
+    /// The condition of `run_until` held
+    Reached(usize),
+    /// The step limit was used up
+    LimitReached(usize),
+    /// The state after `start + length` steps is the same as after `start` steps, so the simulation never ends
+    Cycle { start: usize, length: usize },
+}
+
+pub trait Simulation {
+    /// Everything that decides how the simulation goes on, e.g. position and direction of the guard
+    type State: Hash + Eq;
+
+    /// Advance by one step, false if the simulation is over
+    fn step(&mut self) -> bool;
+
+    fn state(&self) -> Self::State;
+
+    /// Step until `done` holds, it is also checked before the first step
+    fn run_until(&mut self, mut done: impl FnMut(&Self) -> bool, limit: Option<usize>) -> Outcome
+    where
+        Self: Sized,
+    {
+        let mut steps = 0;
+        loop {
+            if done(self) { return Outcome::Reached(steps) }
+            if limit.is_some_and(|limit| steps >= limit) { return Outcome::LimitReached(steps) }
+            if !self.step() { return Outcome::Finished(steps) }
+            steps += 1;
+        }
+    }
+
+    /// Step until the simulation is over
+    fn run(&mut self, limit: Option<usize>) -> Outcome
+    where
+        Self: Sized,
+    {
+        self.run_until(|_| false, limit)
+    }
+
+    /// Step until the simulation is over or a state repeats
+    /// Every state is remembered, so this is meant for simulations of up to a few million steps
+    fn find_cycle(&mut self, limit: Option<usize>) -> Outcome {
+        let mut seen = HashMap::new();
+        let mut steps = 0;
+        loop {
+            match seen.entry(self.state()) {
+                Entry::Occupied(e) => return Outcome::Cycle { start: *e.get(), length: steps - *e.get() },
+                Entry::Vacant(e) => { e.insert(steps); },
+            }
+            if limit.is_some_and(|limit| steps >= limit) { return Outcome::LimitReached(steps) }
+            if !self.step() { return Outcome::Finished(steps) }
+            steps += 1;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // x -> x * x mod m, ends at 0
+    struct Squares {
+        x: u64,
+        m: u64,
+    }
+
+    impl Simulation for Squares {
+        type State = u64;
+
+        fn step(&mut self) -> bool {
+            if self.x == 0 { return false }
+            self.x = self.x * self.x % self.m;
+            true
+        }
+
+        fn state(&self) -> u64 {
+            self.x
+        }
+    }
+
+    #[test]
+    fn test_run() {
+        // 2 4 16 3 9 12 6 13 8 18 2 ...
+        assert_eq!(Squares { x: 2, m: 23 }.run_until(|s| s.x == 9, None), Outcome::Reached(4));
+        assert_eq!(Squares { x: 2, m: 23 }.run(Some(10)), Outcome::LimitReached(10));
+        // 2 4 0
+        assert_eq!(Squares { x: 2, m: 16 }.run(None), Outcome::Finished(2));
+    }
+
+    #[test]
+    fn test_find_cycle() {
+        assert_eq!(Squares { x: 2, m: 23 }.find_cycle(None), Outcome::Cycle { start: 0, length: 10 });
+        // 2 4 16 4 ...
+        assert_eq!(Squares { x: 2, m: 21 }.find_cycle(None), Outcome::Cycle { start: 1, length: 2 });
+        assert_eq!(Squares { x: 2, m: 23 }.find_cycle(Some(3)), Outcome::LimitReached(3));
+        assert_eq!(Squares { x: 2, m: 16 }.find_cycle(None), Outcome::Finished(2));
+    }
+}
@@ -0,0 +1,137 @@
+// Puzzle inputs encrypted at rest, so they can be committed without publishing them
+// The encrypted version of dayN/data.txt is dayN/data.txt.enc, it is used when the plain file is not there.
+// The key is taken from the environment variable AOC2024_VAULT_KEY, any string works but it should be long and random.
+// The plain files are ignored by git, so an input only gets into the repository once it is added with
+// `main vault add dayN/data.txt`, which writes the encrypted file next to the plain one.
+//
+// File format: a header line, then nonce and ciphertext (ChaCha20-Poly1305) as hex in lines of 64 characters
+
+use std::{
+    env, fs,
+    path::{Path, PathBuf},
+};
+
+use chacha20poly1305::{
+    aead::{Aead, KeyInit},
+    ChaCha20Poly1305, Nonce,
+};
+use sha2::{Digest, Sha256};
+
+use crate::embedded;
+
+pub const KEY_VAR: &str = "AOC2024_VAULT_KEY";
+pub const EXTENSION: &str = "enc";
+
+const HEADER: &str = "aoc2024 vault 1";
+const NONCE_LEN: usize = 12;
+const LINE_LEN: usize = 64;
+
+pub struct Key([u8; 32]);
+
+impl Key {
+    pub fn new(secret: &str) -> Self {
+        Key(Sha256::digest(secret.as_bytes()).into())
+    }
+
+    /// The key from the given environment variable
+    pub fn from_var(var: &str) -> Result<Self, String> {
+        match env::var(var) {
+            Ok(secret) if !secret.is_empty() => Ok(Key::new(&secret)),
+            _ => Err(format!("The environment variable {var} with the vault key is not set")),
+        }
+    }
+
+    pub fn from_env() -> Result<Self, String> {
+        Key::from_var(KEY_VAR)
+    }
+
+    fn cipher(&self) -> ChaCha20Poly1305 {
+        ChaCha20Poly1305::new_from_slice(&self.0).unwrap()
+    }
+}
+
+/// Where the encrypted version of a file is kept
+pub fn vault_path(path: impl AsRef<Path>) -> PathBuf {
+    let mut name = path.as_ref().as_os_str().to_owned();
+    name.push(format!(".{EXTENSION}"));
+    PathBuf::from(name)
+}
+
+pub fn encrypt(key: &Key, plaintext: &str) -> String {
+    let mut nonce = [0; NONCE_LEN];
+    getrandom::fill(&mut nonce).expect("no random numbers for the nonce");
+    let ciphertext = key.cipher().encrypt(&Nonce::from(nonce), plaintext.as_bytes()).unwrap();
+
+    let hex = nonce.iter().chain(ciphertext.iter()).map(|b| format!("{b:02x}")).collect::<String>();
+    let mut content = HEADER.to_string();
+    for line in hex.as_bytes().chunks(LINE_LEN) {
+        content.push('\n');
+        content.push_str(std::str::from_utf8(line).unwrap());
+    }
+    content.push('\n');
+    content
+}
+
+pub fn decrypt(key: &Key, content: &str) -> Result<String, String> {
+    let mut lines = content.lines();
+    if lines.next() != Some(HEADER) { return Err("Not a vault file".to_string()) }
+
+    // Byte by byte, any other character than a hex digit means damage
+    let digits = lines
+        .flat_map(|line| line.bytes())
+        .map(|byte| (byte as char).to_digit(16).map(|digit| digit as u8))
+        .collect::<Option<Vec<u8>>>()
+        .ok_or("The vault file is damaged".to_string())?;
+    if digits.len() % 2 != 0 || digits.len() < 2 * NONCE_LEN { return Err("The vault file is damaged".to_string()) }
+    let bytes: Vec<u8> = digits.chunks(2).map(|pair| pair[0] << 4 | pair[1]).collect();
+
+    let (nonce, ciphertext) = bytes.split_at(NONCE_LEN);
+    let nonce = Nonce::from(<[u8; NONCE_LEN]>::try_from(nonce).unwrap());
+    let plaintext = key.cipher().decrypt(&nonce, ciphertext).map_err(|_| "Wrong vault key or damaged file".to_string())?;
+    String::from_utf8(plaintext).map_err(|_| "The decrypted input is not UTF-8".to_string())
+}
+
+// The encrypted content, from disk or embedded into the binary
+fn read_encrypted(path: &Path) -> Option<String> {
+    let vault_path = vault_path(path);
+    fs::read_to_string(&vault_path).ok().or_else(|| embedded::read(&vault_path))
+}
+
+/// Whether there is an encrypted version of the file
+pub fn exists(path: impl AsRef<Path>) -> bool {
+    let vault_path = vault_path(path);
+    vault_path.exists() || embedded::read(&vault_path).is_some()
+}
+
+/// The decrypted file, None if there is no encrypted version
+/// Panics if it can not be decrypted, e.g. because the key is not set
+pub fn read(path: impl AsRef<Path>) -> Option<String> {
+    let path = path.as_ref();
+    let content = read_encrypted(path)?;
+    let plaintext = Key::from_env().and_then(|key| decrypt(&key, &content));
+    Some(plaintext.unwrap_or_else(|e| panic!("Could not decrypt {}: {e}", vault_path(path).display())))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_round_trip() {
+        let key = Key::new("secret");
+        let input = "3   4\n4   3\n2   5\n";
+        let content = encrypt(&key, input);
+        assert!(content.starts_with(HEADER));
+        assert!(!content.contains("3   4"));
+        assert_eq!(decrypt(&key, &content), Ok(input.to_string()));
+        // A new nonce every time
+        assert_ne!(encrypt(&key, input), content);
+
+        assert_eq!(decrypt(&Key::new("guess"), &content), Err("Wrong vault key or damaged file".to_string()));
+        assert_eq!(decrypt(&key, "3   4\n"), Err("Not a vault file".to_string()));
+        let damaged = Err("The vault file is damaged".to_string());
+        assert_eq!(decrypt(&key, &format!("{HEADER}\n0é0\n")), damaged);
+        assert_eq!(decrypt(&key, &format!("{HEADER}\nabc\n")), damaged);
+        assert_eq!(vault_path("day1/data.txt"), PathBuf::from("day1/data.txt.enc"));
+    }
+}